@@ -1,7 +1,10 @@
 use std::path::Path;
 
 use bencher::{benchmark_group, benchmark_main, Bencher};
-use path_absolutize::Absolutize;
+use path_absolutize::{
+    engine::{posix, Limits, Options},
+    Absolutize, MemoAbsolutizer,
+};
 
 fn abs_no_dots(bencher: &mut Bencher) {
     #[cfg(feature = "unsafe_cache")]
@@ -47,6 +50,41 @@ fn abs_mix(bencher: &mut Bencher) {
     bencher.iter(|| path.absolutize());
 }
 
+fn abs_mix_memo_hit(bencher: &mut Bencher) {
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    let memo = MemoAbsolutizer::with_cwd(1024, Path::new(".").absolutize().unwrap());
+
+    let path = Path::new("./path/to/123/../456");
+
+    memo.absolutize(path).unwrap();
+
+    bencher.iter(|| memo.absolutize(path));
+}
+
+fn abs_mix_memo_miss(bencher: &mut Bencher) {
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    // one entry for two paths, so every call misses and evicts the other path
+    let memo = MemoAbsolutizer::with_cwd(1, Path::new(".").absolutize().unwrap());
+
+    let paths = [Path::new("./path/to/123/../456"), Path::new("./path/to/123/../789")];
+
+    let mut i = 0;
+
+    bencher.iter(|| {
+        i ^= 1;
+
+        memo.absolutize(paths[i])
+    });
+}
+
 fn vabs_no_dots(bencher: &mut Bencher) {
     #[cfg(feature = "unsafe_cache")]
     unsafe {
@@ -95,12 +133,59 @@ fn vabs_mix(bencher: &mut Bencher) {
     bencher.iter(|| path.absolutize_virtually(v_root));
 }
 
+fn adversarial_path() -> Vec<u8> {
+    b"a/../".repeat(200_000)
+}
+
+fn limits_unlimited(bencher: &mut Bencher) {
+    let path = adversarial_path();
+
+    bencher.iter(|| posix::try_absolutize_from_with(&path, b"/cwd", &Options::new()));
+}
+
+fn limits_max_input_length(bencher: &mut Bencher) {
+    let path = adversarial_path();
+    let options = Options::new().limits(Limits::new().max_input_length(4096));
+
+    bencher.iter(|| posix::try_absolutize_from_with(&path, b"/cwd", &options));
+}
+
+fn limits_max_components(bencher: &mut Bencher) {
+    let path = adversarial_path();
+    let options = Options::new().limits(Limits::new().max_components(256));
+
+    bencher.iter(|| posix::try_absolutize_from_with(&path, b"/cwd", &options));
+}
+
+fn limits_max_parent_dirs(bencher: &mut Bencher) {
+    let path = adversarial_path();
+    let options = Options::new().limits(Limits::new().max_parent_dirs(64));
+
+    bencher.iter(|| posix::try_absolutize_from_with(&path, b"/cwd", &options));
+}
+
+fn limits_benign_unlimited(bencher: &mut Bencher) {
+    let path = b"./path/to/123/../456";
+
+    bencher.iter(|| posix::try_absolutize_from_with(path, b"/cwd", &Options::new()));
+}
+
+fn limits_benign_limited(bencher: &mut Bencher) {
+    let path = b"./path/to/123/../456";
+    let options = Options::new()
+        .limits(Limits::new().max_input_length(4096).max_components(256).max_parent_dirs(64));
+
+    bencher.iter(|| posix::try_absolutize_from_with(path, b"/cwd", &options));
+}
+
 benchmark_group!(
     absolutize,
     abs_no_dots,
     abs_starts_with_a_single_dot,
     abs_starts_with_double_dots,
-    abs_mix
+    abs_mix,
+    abs_mix_memo_hit,
+    abs_mix_memo_miss
 );
 benchmark_group!(
     absolutize_virtually,
@@ -109,4 +194,13 @@ benchmark_group!(
     vabs_starts_with_double_dots,
     vabs_mix
 );
-benchmark_main!(absolutize, absolutize_virtually);
+benchmark_group!(
+    limits,
+    limits_unlimited,
+    limits_max_input_length,
+    limits_max_components,
+    limits_max_parent_dirs,
+    limits_benign_unlimited,
+    limits_benign_limited
+);
+benchmark_main!(absolutize, absolutize_virtually, limits);
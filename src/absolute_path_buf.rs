@@ -0,0 +1,92 @@
+use std::{
+    borrow::Borrow,
+    ffi::OsStr,
+    io::{self, ErrorKind},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use crate::Absolutize;
+
+/// An owned path which is guaranteed to be absolute and free of dots.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbsolutePathBuf(PathBuf);
+
+impl AbsolutePathBuf {
+    /// Absolutize a path and wrap the result.
+    #[inline]
+    pub fn new(path: impl AsRef<Path>) -> io::Result<AbsolutePathBuf> {
+        let path = path.as_ref().absolutize()?;
+
+        Self::from_absolutized(path.into_owned())
+    }
+
+    /// Absolutize a path against the given current working directory and wrap the result. The current working directory needs to be absolute.
+    #[inline]
+    pub fn new_from(path: impl AsRef<Path>, cwd: impl AsRef<Path>) -> io::Result<AbsolutePathBuf> {
+        let path = path.as_ref().absolutize_from(cwd)?;
+
+        Self::from_absolutized(path.into_owned())
+    }
+
+    #[inline]
+    fn from_absolutized(path: PathBuf) -> io::Result<AbsolutePathBuf> {
+        if path.is_absolute() {
+            Ok(AbsolutePathBuf(path))
+        } else {
+            Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "The current working directory is not absolute.",
+            ))
+        }
+    }
+
+    /// Borrow the inner path.
+    #[inline]
+    pub fn as_path(&self) -> &Path {
+        self.0.as_path()
+    }
+
+    /// Unwrap the inner path.
+    #[inline]
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+}
+
+impl Deref for AbsolutePathBuf {
+    type Target = Path;
+
+    #[inline]
+    fn deref(&self) -> &Path {
+        self.0.as_path()
+    }
+}
+
+impl AsRef<Path> for AbsolutePathBuf {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.0.as_path()
+    }
+}
+
+impl AsRef<OsStr> for AbsolutePathBuf {
+    #[inline]
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl Borrow<Path> for AbsolutePathBuf {
+    #[inline]
+    fn borrow(&self) -> &Path {
+        self.0.as_path()
+    }
+}
+
+impl From<AbsolutePathBuf> for PathBuf {
+    #[inline]
+    fn from(path: AbsolutePathBuf) -> PathBuf {
+        path.0
+    }
+}
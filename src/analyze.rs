@@ -0,0 +1,21 @@
+use std::path::Path;
+
+use crate::engine::analyze::{AnalysisRules, PathReport};
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+/// Find the anomalies in a path without rewriting it, like redundant separators, `./` prefixes, `..` which climbs out of the base directory and trailing separators, by the same rules as the engine which this crate uses on the host. See `analyze_with`.
+#[inline]
+pub fn analyze(path: &Path) -> PathReport {
+    analyze_with(path, &AnalysisRules::new())
+}
+
+/// Find the anomalies in a path without rewriting it by `rules`. The spans are byte ranges of the bytes which the engine works on, which are the bytes of the path on Unix-like systems and WTF-8 on Windows, so they are the same as the spans in `to_str()` for paths of valid Unicode.
+#[inline]
+pub fn analyze_with(path: &Path, rules: &AnalysisRules) -> PathReport {
+    let path = host::to_bytes(path.as_os_str());
+
+    host::engine::analyze(&path, rules)
+}
@@ -0,0 +1,44 @@
+use std::{
+    io::{self, ErrorKind},
+    path::{Component, Path, PathBuf},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+use crate::{
+    engine::archive::{sanitize_entry_name, ArchivePolicy},
+    Absolutize,
+};
+
+/// Get the path which an entry of an archive, like a zip or tar file, should be extracted to, confined to the destination directory `dest`. `dest` is absolutized.
+///
+/// Both `/` and `\` are separators whatever the host is, Windows prefixes like `C:` and `\\server\share` and root directories are dropped, and a `..` which would leave `dest` is handled according to `policy`. See `engine::archive::sanitize_entry_name`.
+///
+/// Returns an error of `ErrorKind::InvalidInput` if the name is rejected by `policy`, or if its first name would be parsed as a drive prefix, like `C:` in `x/../C:/y`.
+pub fn sanitize_archive_entry(
+    name: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    policy: ArchivePolicy,
+) -> io::Result<PathBuf> {
+    let name = host::to_bytes(name.as_ref().as_os_str());
+
+    let sanitized = host::from_bytes(sanitize_entry_name(&name, policy)?);
+
+    let mut path = dest.as_ref().absolutize()?.into_owned();
+
+    for component in sanitized.components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "The entry name has a component which is not a normal file name.",
+                ))
+            },
+        }
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,104 @@
+use std::{
+    borrow::Cow,
+    io::{self, ErrorKind},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+/// Let `Utf8Path` and `Utf8PathBuf` have `absolutize` and `absolutize_virtually` method.
+pub trait Utf8Absolutize {
+    /// Get an absolute path. This works even if the path does not exist.
+    fn absolutize(&self) -> io::Result<Cow<'_, Utf8Path>>;
+
+    /// Get an absolute path. This works even if the path does not exist. It gets the current working directory as the second argument.
+    fn absolutize_from(&self, cwd: impl AsRef<Utf8Path>) -> io::Result<Cow<'_, Utf8Path>>;
+
+    /// Get an absolute path. This works even if the path does not exist.
+    fn absolutize_virtually(
+        &self,
+        virtual_root: impl AsRef<Utf8Path>,
+    ) -> io::Result<Cow<'_, Utf8Path>>;
+}
+
+/// Convert a result of the engine, which only contains UTF-8 text if the current working directory is valid UTF-8.
+#[inline]
+fn from_engine<'a>(path: &'a Utf8Path, result: Cow<'_, [u8]>) -> io::Result<Cow<'a, Utf8Path>> {
+    match result {
+        Cow::Borrowed(_) => Ok(Cow::Borrowed(path)),
+        Cow::Owned(bytes) => {
+            String::from_utf8(bytes).map(|s| Cow::Owned(Utf8PathBuf::from(s))).map_err(|_| {
+                io::Error::new(
+                    ErrorKind::Other,
+                    "The current working directory is not valid UTF-8.",
+                )
+            })
+        },
+    }
+}
+
+impl Utf8Absolutize for Utf8Path {
+    #[inline]
+    fn absolutize(&self) -> io::Result<Cow<'_, Utf8Path>> {
+        let cwd = get_cwd!();
+
+        let cwd = host::to_bytes(cwd.as_os_str());
+
+        from_engine(self, host::absolutize(self.as_str().as_bytes(), &cwd))
+    }
+
+    #[inline]
+    fn absolutize_from(&self, cwd: impl AsRef<Utf8Path>) -> io::Result<Cow<'_, Utf8Path>> {
+        // both the path and the CWD are UTF-8, so is the result
+        from_engine(
+            self,
+            host::engine::absolutize_from(
+                self.as_str().as_bytes(),
+                cwd.as_ref().as_str().as_bytes(),
+            ),
+        )
+    }
+
+    #[inline]
+    fn absolutize_virtually(
+        &self,
+        virtual_root: impl AsRef<Utf8Path>,
+    ) -> io::Result<Cow<'_, Utf8Path>> {
+        let cwd = get_cwd!();
+
+        let cwd = host::to_bytes(cwd.as_os_str());
+
+        from_engine(
+            self,
+            host::absolutize_virtually(
+                self.as_str().as_bytes(),
+                virtual_root.as_ref().as_str().as_bytes(),
+                &cwd,
+            )?,
+        )
+    }
+}
+
+impl Utf8Absolutize for Utf8PathBuf {
+    #[inline]
+    fn absolutize(&self) -> io::Result<Cow<'_, Utf8Path>> {
+        self.as_path().absolutize()
+    }
+
+    #[inline]
+    fn absolutize_from(&self, cwd: impl AsRef<Utf8Path>) -> io::Result<Cow<'_, Utf8Path>> {
+        self.as_path().absolutize_from(cwd)
+    }
+
+    #[inline]
+    fn absolutize_virtually(
+        &self,
+        virtual_root: impl AsRef<Utf8Path>,
+    ) -> io::Result<Cow<'_, Utf8Path>> {
+        self.as_path().absolutize_virtually(virtual_root)
+    }
+}
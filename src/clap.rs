@@ -0,0 +1,135 @@
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+};
+
+use clap::{builder::TypedValueParser, error::ErrorKind, Arg, Command, Error};
+
+use crate::Absolutize;
+
+/// A `TypedValueParser` which absolutizes path arguments while they are parsed.
+///
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// use clap::{Arg, Command};
+/// use path_absolutize::clap::AbsolutizeValueParser;
+///
+/// let matches = Command::new("app")
+///     .arg(
+///         Arg::new("input")
+///             .long("input")
+///             .value_parser(AbsolutizeValueParser::new()),
+///     )
+///     .get_matches_from(["app", "--input", "path/to/../123"]);
+///
+/// let input: &PathBuf = matches.get_one("input").unwrap();
+///
+/// assert!(input.is_absolute());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AbsolutizeValueParser {
+    mode: Mode,
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Cwd,
+    Virtual(PathBuf),
+    Within(PathBuf),
+}
+
+impl Default for Mode {
+    #[inline]
+    fn default() -> Self {
+        Mode::Cwd
+    }
+}
+
+impl AbsolutizeValueParser {
+    /// Absolutize arguments against the current working directory.
+    #[inline]
+    pub fn new() -> AbsolutizeValueParser {
+        AbsolutizeValueParser::default()
+    }
+
+    /// Absolutize arguments with the `absolutize_virtually` method, so every argument needs to be under the virtual root.
+    #[inline]
+    pub fn virtually(virtual_root: impl Into<PathBuf>) -> AbsolutizeValueParser {
+        AbsolutizeValueParser {
+            mode: Mode::Virtual(virtual_root.into())
+        }
+    }
+
+    /// Resolve relative arguments against the root, and reject any argument which climbs above the root with `..` or points outside of it.
+    #[inline]
+    pub fn within(root: impl Into<PathBuf>) -> AbsolutizeValueParser {
+        AbsolutizeValueParser {
+            mode: Mode::Within(root.into())
+        }
+    }
+
+    fn absolutize(&self, path: &Path) -> Result<PathBuf, String> {
+        match &self.mode {
+            Mode::Cwd => path.absolutize().map(|p| p.into_owned()).map_err(|err| err.to_string()),
+            Mode::Virtual(virtual_root) => match path.absolutize_virtually(virtual_root) {
+                Ok(p) => Ok(p.into_owned()),
+                Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                    Err(format!("it is outside of the virtual root {:?}", virtual_root))
+                },
+                Err(err) => Err(err.to_string()),
+            },
+            Mode::Within(root) => {
+                let root = root.absolutize().map_err(|err| err.to_string())?;
+
+                // joining keeps the `..` components, so a relative argument which climbs above the root resolves outside of it
+                let joined;
+
+                let path = if path.is_absolute() {
+                    path
+                } else {
+                    joined = root.join(path);
+
+                    joined.as_path()
+                };
+
+                match path.absolutize_virtually(&root) {
+                    Ok(p) => Ok(p.into_owned()),
+                    Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                        Err(format!("it is outside of {:?}", root))
+                    },
+                    Err(err) => Err(err.to_string()),
+                }
+            },
+        }
+    }
+}
+
+impl TypedValueParser for AbsolutizeValueParser {
+    type Value = PathBuf;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let reason = if value.is_empty() {
+            String::from("a path cannot be empty")
+        } else {
+            match self.absolutize(Path::new(value)) {
+                Ok(path) => return Ok(path),
+                Err(reason) => reason,
+            }
+        };
+
+        let arg = arg.map(|arg| arg.to_string()).unwrap_or_else(|| String::from("..."));
+
+        Err(Error::raw(
+            ErrorKind::ValueValidation,
+            format!("invalid value {:?} for '{}': {}\n", value, arg, reason),
+        )
+        .with_cmd(cmd))
+    }
+}
@@ -0,0 +1,232 @@
+//! Analysis of paths which reports anomalies, i.e., things which are valid but suspicious, like `a//b` or `./a`, without rewriting the paths. See the `analyze` function of the engines.
+
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+/// A kind of anomalies in a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// A separator follows another separator, like the second `/` in `a//b`.
+    RedundantSeparator,
+    /// A `.`, like in `./a` or `a/./b`.
+    CurDir,
+    /// A `..` which climbs out of the directory which a relative path starts from, like in `a/../../b`, or above the root directory, like in `/..`.
+    ParentDirEscape,
+    /// A separator which differs from the first separator in a Windows path, like the `/` in `a\b/c`.
+    MixedSeparators,
+    /// The path ends with separators, like `a/b/`.
+    TrailingSeparator,
+    /// The path has a prefix or a root directory, but a relative path is expected. See `AnalysisRules::expect_relative`.
+    NotRelative,
+}
+
+impl Display for AnomalyKind {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AnomalyKind::RedundantSeparator => f.write_str("redundant separator"),
+            AnomalyKind::CurDir => f.write_str("redundant `.`"),
+            AnomalyKind::ParentDirEscape => f.write_str("`..` climbs out of the base directory"),
+            AnomalyKind::MixedSeparators => f.write_str("mixed separators"),
+            AnomalyKind::TrailingSeparator => f.write_str("trailing separator"),
+            AnomalyKind::NotRelative => f.write_str("a relative path is expected"),
+        }
+    }
+}
+
+/// An anomaly in a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    /// What is suspicious.
+    pub kind: AnomalyKind,
+    /// The byte span of the anomaly in the path.
+    pub span: Range<usize>,
+}
+
+impl Display for Anomaly {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+/// Rules of analysis. By default, every path can be relative or absolute.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalysisRules {
+    pub(crate) expect_relative: bool,
+}
+
+impl AnalysisRules {
+    /// Create rules with the default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a relative path is expected, so a prefix or a root directory is reported as `AnomalyKind::NotRelative`. The default value is `false`.
+    #[inline]
+    pub fn expect_relative(mut self, expect_relative: bool) -> Self {
+        self.expect_relative = expect_relative;
+
+        self
+    }
+}
+
+/// The anomalies found in a path, ordered by their positions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathReport {
+    pub(crate) anomalies: Vec<Anomaly>,
+}
+
+impl PathReport {
+    /// Get the anomalies.
+    #[inline]
+    pub fn anomalies(&self) -> &[Anomaly] {
+        &self.anomalies
+    }
+
+    /// Whether there is no anomaly.
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// Whether there is an anomaly of `kind`.
+    #[inline]
+    pub fn contains(&self, kind: AnomalyKind) -> bool {
+        self.anomalies.iter().any(|anomaly| anomaly.kind == kind)
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, kind: AnomalyKind, span: Range<usize>) {
+        self.anomalies.push(Anomaly {
+            kind,
+            span,
+        });
+    }
+
+    #[inline]
+    pub(crate) fn finish(mut self) -> Self {
+        self.anomalies.sort_by_key(|anomaly| anomaly.span.start);
+
+        self
+    }
+}
+
+impl Display for PathReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, anomaly) in self.anomalies.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            Display::fmt(anomaly, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A component of a path with its byte span, which the engines give to `report_parts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Part {
+    RootDir,
+    CurDir,
+    ParentDir,
+    Normal,
+}
+
+/// Report the anomalies of the components of `path` after `start`, which is the end of the prefix. The components skip empty names and `.` after the first component, so the gaps between their spans only have separators and `.`.
+pub(crate) fn report_parts(
+    report: &mut PathReport,
+    path: &[u8],
+    start: usize,
+    parts: impl Iterator<Item = (Part, Range<usize>)>,
+    is_separator: impl Fn(u8) -> bool,
+) {
+    let mut depth = 0usize;
+
+    let mut gap_start = start;
+    let mut after_root = false;
+
+    for (part, span) in parts {
+        report_gap(report, path, gap_start..span.start, after_root, false, &is_separator);
+
+        match part {
+            Part::RootDir | Part::Normal => (),
+            Part::CurDir => report.push(AnomalyKind::CurDir, span.clone()),
+            Part::ParentDir => {
+                if depth == 0 {
+                    report.push(AnomalyKind::ParentDirEscape, span.clone());
+                } else {
+                    depth -= 1;
+                }
+            },
+        }
+
+        if part == Part::Normal {
+            depth += 1;
+        }
+
+        after_root = part == Part::RootDir;
+        gap_start = span.end;
+    }
+
+    report_gap(report, path, gap_start..path.len(), after_root, true, &is_separator);
+}
+
+/// Report the separators and `.` between two components. Every separator right after the root directory is redundant, because the root directory is a separator itself.
+fn report_gap(
+    report: &mut PathReport,
+    path: &[u8],
+    gap: Range<usize>,
+    after_root: bool,
+    at_end: bool,
+    is_separator: &impl Fn(u8) -> bool,
+) {
+    let mut i = gap.start;
+
+    while i < gap.end {
+        let run_end = i + path[i..gap.end].iter().take_while(|&&b| is_separator(b)).count();
+
+        if run_end > i {
+            if after_root && i == gap.start {
+                report.push(AnomalyKind::RedundantSeparator, i..run_end);
+            } else if at_end && run_end == gap.end {
+                report.push(AnomalyKind::TrailingSeparator, i..run_end);
+            } else if run_end - i > 1 {
+                report.push(AnomalyKind::RedundantSeparator, i + 1..run_end);
+            }
+
+            i = run_end;
+        } else {
+            let name_end = i + path[i..gap.end].iter().take_while(|&&b| !is_separator(b)).count();
+
+            report.push(AnomalyKind::CurDir, i..name_end);
+
+            i = name_end;
+        }
+    }
+}
+
+/// Get the span of a name which the components of `path` have just returned, from the length of the rest of `path`, which starts after the separator following the name, if any.
+#[inline]
+pub(crate) fn name_span(
+    path: &[u8],
+    rest_length: usize,
+    name_length: usize,
+    is_separator: impl Fn(u8) -> bool,
+) -> Range<usize> {
+    let rest_start = path.len() - rest_length;
+
+    let end = if rest_start > 0 && is_separator(path[rest_start - 1]) {
+        rest_start - 1
+    } else {
+        rest_start
+    };
+
+    end - name_length..end
+}
@@ -0,0 +1,72 @@
+//! Sanitization of the entry names of archives, like zip and tar files, so that extracting an entry cannot write outside the destination directory ("zip slip").
+
+use alloc::vec::Vec;
+
+use super::{
+    windows::{self, Win32IssueKind},
+    Error,
+};
+
+/// What to do with a `..` which would leave the destination directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchivePolicy {
+    /// Drop it, so `../../etc/x` becomes `etc/x`.
+    Clamp,
+    /// Reject the whole name with `Error::OutsideVirtualRoot`.
+    Reject,
+}
+
+impl Default for ArchivePolicy {
+    #[inline]
+    fn default() -> Self {
+        ArchivePolicy::Clamp
+    }
+}
+
+/// Sanitize the entry name of an archive into a relative path whose names are joined with `/`. The result is empty if the entry means the destination directory itself, like `./`.
+///
+/// Both `/` and `\` are separators whatever the host is. Windows prefixes, like `C:`, `\\server\share` and `\\?\C:`, and root directories are dropped, `.` and empty names are ignored, and `..` is resolved, while a `..` which would leave the destination directory is handled according to `policy`.
+///
+/// A colon is kept in a name like `12:30.txt`, but if the first name of the result would be parsed as a drive prefix by Windows, like `C:` in `x/../C:/y`, the name is rejected with `Error::Win32Name` whatever `policy` is, so the result can never start with a prefix. `index` counts the names after the prefix, including `.` and `..`.
+pub fn sanitize_entry_name(name: &[u8], policy: ArchivePolicy) -> Result<Vec<u8>, Error> {
+    let start = windows::get_path_prefix(name).map(|prefix| prefix.as_bytes().len()).unwrap_or(0);
+
+    let mut tokens: Vec<(usize, &[u8])> = Vec::new();
+
+    for (index, token) in name[start..]
+        .split(|&b| windows::is_separator(b))
+        .filter(|token| !token.is_empty())
+        .enumerate()
+    {
+        match token {
+            b"." => (),
+            b".." => {
+                if tokens.pop().is_none() && policy == ArchivePolicy::Reject {
+                    return Err(Error::OutsideVirtualRoot);
+                }
+            },
+            _ => tokens.push((index, token)),
+        }
+    }
+
+    if let Some(&(index, token)) = tokens.first() {
+        if windows::get_path_prefix(token).is_some() {
+            return Err(Error::Win32Name {
+                kind: Win32IssueKind::AlternateDataStream,
+                index,
+            });
+        }
+    }
+
+    let mut sanitized = Vec::with_capacity(name.len());
+
+    for (i, (_, token)) in tokens.into_iter().enumerate() {
+        if i > 0 {
+            sanitized.push(b'/');
+        }
+
+        sanitized.extend_from_slice(token);
+    }
+
+    Ok(sanitized)
+}
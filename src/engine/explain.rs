@@ -0,0 +1,149 @@
+//! The steps which the engines take to absolutize a path, for finding out why a path is resolved to something unexpected.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use super::SeparatorStyle;
+
+/// How the Windows engine handles the prefix of a path, like `C:` or `\\server\share`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixHandling {
+    /// The prefix is followed by a root directory, so it is kept.
+    Kept,
+    /// The prefix is the whole path, so a root directory is added after it.
+    RootAdded,
+    /// The prefix is followed by a relative path, like `D:foo`, so it is resolved from `cwd`, which is the current working directory of the drive, or its parent if `parent` is `true`.
+    ResolvedFromCwd { cwd: Vec<u8>, parent: bool },
+    /// The path begins with a root directory without a prefix, so the prefix is taken from the current working directory.
+    TakenFromCwd,
+}
+
+/// A step which an engine takes to absolutize a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The path is empty, so the result is the current working directory.
+    Empty,
+    /// The path is kept as it is, like a verbatim path on Windows.
+    Literal,
+    /// The names of the path are normalized the same way as Win32 does. See `Options::win32_normalization`.
+    Win32Normalized { path: Vec<u8> },
+    /// The path is relative, so it is resolved from `cwd`, or from its parent if the path begins with `..`.
+    ResolvedFromCwd { cwd: Vec<u8>, parent: bool },
+    /// The prefix of a Windows path is handled.
+    Prefix { prefix: Vec<u8>, handling: PrefixHandling },
+    /// The root directory is kept.
+    Root { root: Vec<u8> },
+    /// A `.` is removed.
+    CurDirRemoved,
+    /// A name is kept.
+    Kept { name: Vec<u8> },
+    /// A `..` removes the name before it.
+    ParentDirRemoved { name: Vec<u8> },
+    /// A `..` is clamped, because there is no name before it to remove, like `/..`.
+    ParentDirClamped,
+    /// The separators are rewritten. See `Options::separator_style`.
+    SeparatorsRewritten { style: SeparatorStyle },
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Display for Bytes<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "`{}`", String::from_utf8_lossy(self.0))
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Step::Empty => f.write_str("the path is empty, so it is the current working directory"),
+            Step::Literal => f.write_str("the path is kept as it is"),
+            Step::Win32Normalized {
+                path,
+            } => write!(f, "normalized the names as Win32 does into {}", Bytes(path)),
+            Step::ResolvedFromCwd {
+                cwd,
+                parent,
+            } => {
+                if *parent {
+                    write!(
+                        f,
+                        "resolved from the parent of the current working directory {}",
+                        Bytes(cwd)
+                    )
+                } else {
+                    write!(f, "resolved from the current working directory {}", Bytes(cwd))
+                }
+            },
+            Step::Prefix {
+                prefix,
+                handling,
+            } => match handling {
+                PrefixHandling::Kept => write!(f, "kept the prefix {}", Bytes(prefix)),
+                PrefixHandling::RootAdded => {
+                    write!(f, "added a root directory after the prefix {}", Bytes(prefix))
+                },
+                PrefixHandling::ResolvedFromCwd {
+                    cwd,
+                    parent,
+                } => {
+                    if *parent {
+                        write!(
+                            f,
+                            "resolved the prefix {} from the parent of the current working \
+                             directory {}",
+                            Bytes(prefix),
+                            Bytes(cwd)
+                        )
+                    } else {
+                        write!(
+                            f,
+                            "resolved the prefix {} from the current working directory {}",
+                            Bytes(prefix),
+                            Bytes(cwd)
+                        )
+                    }
+                },
+                PrefixHandling::TakenFromCwd => write!(
+                    f,
+                    "took the prefix {} from the current working directory",
+                    Bytes(prefix)
+                ),
+            },
+            Step::Root {
+                root,
+            } => write!(f, "kept the root directory {}", Bytes(root)),
+            Step::CurDirRemoved => f.write_str("removed `.`"),
+            Step::Kept {
+                name,
+            } => write!(f, "kept {}", Bytes(name)),
+            Step::ParentDirRemoved {
+                name,
+            } => write!(f, "`..` removed {}", Bytes(name)),
+            Step::ParentDirClamped => {
+                f.write_str("`..` is clamped, because there is nothing to remove")
+            },
+            Step::SeparatorsRewritten {
+                style,
+            } => write!(f, "rewrote the separators in the style {:?}", style),
+        }
+    }
+}
+
+/// A recorder of steps. The engines record steps with closures, so that nothing is built when the steps are not needed.
+pub(crate) trait Trace {
+    fn record(&mut self, step: impl FnOnce() -> Step);
+}
+
+impl Trace for () {
+    #[inline(always)]
+    fn record(&mut self, _step: impl FnOnce() -> Step) {}
+}
+
+impl Trace for Vec<Step> {
+    #[inline]
+    fn record(&mut self, step: impl FnOnce() -> Step) {
+        self.push(step());
+    }
+}
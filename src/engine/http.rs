@@ -0,0 +1,225 @@
+//! Decoding and validation of the paths of HTTP requests for static file servers.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use super::Error;
+
+/// What to do with a trailing slash of a request path, like `/docs/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Drop it, so `/docs/` means the same as `/docs`.
+    Strip,
+    /// Keep it, so the server can tell that a directory is requested.
+    Keep,
+    /// Reject the request path.
+    Reject,
+}
+
+impl Default for TrailingSlash {
+    #[inline]
+    fn default() -> Self {
+        TrailingSlash::Strip
+    }
+}
+
+/// Policies of decoding request paths. By default, encoded slashes, backslashes and dotfiles are rejected, and trailing slashes are stripped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestPolicy {
+    pub(crate) encoded_slashes: bool,
+    pub(crate) backslashes:     bool,
+    pub(crate) dotfiles:        bool,
+    pub(crate) trailing_slash:  TrailingSlash,
+}
+
+impl RequestPolicy {
+    /// Create policies with the default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `%2F` is accepted as a separator. The default value is `false`, which rejects it.
+    #[inline]
+    pub fn encoded_slashes(mut self, encoded_slashes: bool) -> Self {
+        self.encoded_slashes = encoded_slashes;
+
+        self
+    }
+
+    /// Whether `\` and `%5C` are accepted as separators. The default value is `false`, which rejects them.
+    #[inline]
+    pub fn backslashes(mut self, backslashes: bool) -> Self {
+        self.backslashes = backslashes;
+
+        self
+    }
+
+    /// Whether names beginning with a dot, like `.git` or `.env`, are accepted. The default value is `false`, which rejects them.
+    #[inline]
+    pub fn dotfiles(mut self, dotfiles: bool) -> Self {
+        self.dotfiles = dotfiles;
+
+        self
+    }
+
+    /// Set what to do with a trailing slash. The default value is `TrailingSlash::Strip`.
+    #[inline]
+    pub fn trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+
+        self
+    }
+}
+
+/// A reason why a request path is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestPathIssue {
+    /// A `%` is not followed by two hexadecimal digits.
+    InvalidPercentEncoding,
+    /// The path contains a NUL byte, raw or as `%00`.
+    Nul,
+    /// The path contains `%2F`.
+    EncodedSlash,
+    /// The path contains `\` or `%5C`.
+    Backslash,
+    /// A name begins with a dot.
+    Dotfile,
+    /// The path ends with a slash.
+    TrailingSlash,
+}
+
+impl Display for RequestPathIssue {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RequestPathIssue::InvalidPercentEncoding => {
+                f.write_str("it has a `%` which is not followed by two hexadecimal digits")
+            },
+            RequestPathIssue::Nul => f.write_str("it contains a NUL byte"),
+            RequestPathIssue::EncodedSlash => f.write_str("it contains an encoded slash"),
+            RequestPathIssue::Backslash => f.write_str("it contains a backslash"),
+            RequestPathIssue::Dotfile => f.write_str("it has a name which begins with a dot"),
+            RequestPathIssue::TrailingSlash => f.write_str("it ends with a slash"),
+        }
+    }
+}
+
+#[inline]
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Percent-decode one segment between literal separators, and split it at the separators which the policies accept in encoded form.
+fn decode_segment(
+    segment: &[u8],
+    policy: &RequestPolicy,
+    names: &mut Vec<Vec<u8>>,
+) -> Result<(), Error> {
+    let mut name = Vec::with_capacity(segment.len());
+
+    let mut i = 0;
+
+    while i < segment.len() {
+        let b = match segment[i] {
+            b'%' => {
+                let high = segment.get(i + 1).copied().and_then(hex_value);
+                let low = segment.get(i + 2).copied().and_then(hex_value);
+
+                match (high, low) {
+                    (Some(high), Some(low)) => {
+                        i += 3;
+
+                        let b = high << 4 | low;
+
+                        match b {
+                            b'/' if !policy.encoded_slashes => {
+                                return Err(Error::RequestPath(RequestPathIssue::EncodedSlash))
+                            },
+                            b'/' => {
+                                names.push(core::mem::take(&mut name));
+
+                                continue;
+                            },
+                            _ => b,
+                        }
+                    },
+                    _ => return Err(Error::RequestPath(RequestPathIssue::InvalidPercentEncoding)),
+                }
+            },
+            b => {
+                i += 1;
+
+                b
+            },
+        };
+
+        match b {
+            0 => return Err(Error::RequestPath(RequestPathIssue::Nul)),
+            b'\\' if !policy.backslashes => {
+                return Err(Error::RequestPath(RequestPathIssue::Backslash))
+            },
+            b'\\' => names.push(core::mem::take(&mut name)),
+            _ => name.push(b),
+        }
+    }
+
+    names.push(name);
+
+    Ok(())
+}
+
+/// Decode the path of an HTTP request, without the query and the fragment, into a relative path under the document root whose names are joined with `/`. The result is empty for the document root itself, and ends with `/` if the request path does and `TrailingSlash::Keep` is set.
+///
+/// The path is percent-decoded and validated according to `policy`, and `.` and `..` are resolved. A `..` which would leave the document root is rejected with `Error::OutsideVirtualRoot`, and other problems with `Error::RequestPath`. The result is not always valid UTF-8.
+pub fn decode_request_path(url_path: &[u8], policy: &RequestPolicy) -> Result<Vec<u8>, Error> {
+    let mut names = Vec::new();
+
+    for segment in url_path.split(|&b| b == b'/') {
+        decode_segment(segment, policy, &mut names)?;
+    }
+
+    let mut tokens: Vec<Vec<u8>> = Vec::with_capacity(names.len());
+
+    for name in names {
+        match name.as_slice() {
+            b"" | b"." => (),
+            b".." => {
+                if tokens.pop().is_none() {
+                    return Err(Error::OutsideVirtualRoot);
+                }
+            },
+            [b'.', ..] if !policy.dotfiles => {
+                return Err(Error::RequestPath(RequestPathIssue::Dotfile))
+            },
+            _ => tokens.push(name),
+        }
+    }
+
+    let mut decoded = Vec::with_capacity(url_path.len());
+
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            decoded.push(b'/');
+        }
+
+        decoded.extend_from_slice(token);
+    }
+
+    if !decoded.is_empty() && url_path.ends_with(b"/") {
+        match policy.trailing_slash {
+            TrailingSlash::Strip => (),
+            TrailingSlash::Keep => decoded.push(b'/'),
+            TrailingSlash::Reject => {
+                return Err(Error::RequestPath(RequestPathIssue::TrailingSlash))
+            },
+        }
+    }
+
+    Ok(decoded)
+}
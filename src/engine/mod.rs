@@ -0,0 +1,343 @@
+//! The host-independent engines which do the actual work of this crate.
+//!
+//! They work on raw bytes and need only `alloc`, so they are available without the `std` feature. Paths in `posix` are arbitrary bytes, and paths in `windows` are WTF-8 bytes, which can encode every Windows path losslessly (see `windows::wtf8`).
+
+pub mod analyze;
+pub mod archive;
+pub mod explain;
+pub mod http;
+pub mod policy;
+pub mod posix;
+pub mod translate;
+pub mod windows;
+
+use alloc::vec::Vec;
+use core::{
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+};
+
+use http::RequestPathIssue;
+use policy::{PathPolicy, PolicyViolationKind};
+use windows::{DriveCwds, Win32IssueKind};
+
+/// The separators in the paths returned by the Windows engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorStyle {
+    /// Join components with `\`, and keep the separators of the input where the input is kept.
+    Native,
+    /// Use `/` everywhere, like `C:/a/b` or `//server/share/a`.
+    ForwardSlash,
+    /// Use `\` everywhere, like `C:\a\b` or `\\server\share\a`.
+    Backslash,
+}
+
+impl Default for SeparatorStyle {
+    #[inline]
+    fn default() -> Self {
+        SeparatorStyle::Native
+    }
+}
+
+/// Limits which protect the engines against adversarial paths, like megabyte-long paths with thousands of `a/../` segments. A limit of `None` means no limit, which is the default.
+///
+/// The input limits are checked while the engines walk the input path, so the walk stops at the first component over a limit. Only the `try_absolutize_from_with` and `absolutize_virtually_with` functions of the engines check the limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub(crate) max_input_length:  Option<usize>,
+    pub(crate) max_output_length: Option<usize>,
+    pub(crate) max_components:    Option<usize>,
+    pub(crate) max_parent_dirs:   Option<usize>,
+}
+
+impl Limits {
+    /// Create limits without any limit.
+    #[inline]
+    pub const fn new() -> Self {
+        Limits {
+            max_input_length:  None,
+            max_output_length: None,
+            max_components:    None,
+            max_parent_dirs:   None,
+        }
+    }
+
+    /// Set the maximum length of the input path in bytes.
+    #[inline]
+    pub const fn max_input_length(mut self, max_input_length: usize) -> Self {
+        self.max_input_length = Some(max_input_length);
+
+        self
+    }
+
+    /// Set the maximum length of the output path in bytes, like `PATH_MAX` on POSIX or `windows::MAX_PATH` on Windows.
+    #[inline]
+    pub const fn max_output_length(mut self, max_output_length: usize) -> Self {
+        self.max_output_length = Some(max_output_length);
+
+        self
+    }
+
+    /// Set the maximum number of components of the input path, including the prefix and the root directory.
+    #[inline]
+    pub const fn max_components(mut self, max_components: usize) -> Self {
+        self.max_components = Some(max_components);
+
+        self
+    }
+
+    /// Set the maximum number of `..` in the input path.
+    #[inline]
+    pub const fn max_parent_dirs(mut self, max_parent_dirs: usize) -> Self {
+        self.max_parent_dirs = Some(max_parent_dirs);
+
+        self
+    }
+
+    /// Get a counter which checks the input limits while the engines walk the input path.
+    #[inline]
+    pub(crate) fn counter(&self) -> LimitCounter {
+        LimitCounter {
+            max_input_length: self.max_input_length.unwrap_or(usize::MAX),
+            max_components:   self.max_components.unwrap_or(usize::MAX),
+            max_parent_dirs:  self.max_parent_dirs.unwrap_or(usize::MAX),
+            components:       0,
+            parent_dirs:      0,
+        }
+    }
+
+    /// Check the length of the output path.
+    #[inline]
+    pub(crate) fn check_output(&self, length: usize) -> Result<(), Error> {
+        if self.max_output_length.map(|max| length > max).unwrap_or(false) {
+            return Err(Error::LimitExceeded(Limit::OutputLength));
+        }
+
+        Ok(())
+    }
+}
+
+/// A counter of the components of the input path which the engines walk. `()` counts nothing.
+pub(crate) trait Count {
+    type Error;
+
+    fn check_length(&mut self, length: usize) -> Result<(), Self::Error>;
+
+    fn count(&mut self, parent_dir: bool) -> Result<(), Self::Error>;
+}
+
+impl Count for () {
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn check_length(&mut self, _length: usize) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn count(&mut self, _parent_dir: bool) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Unwrap the result of a walk with `()` as the counter.
+#[inline]
+pub(crate) fn unwrap_infallible<T>(result: Result<T, Infallible>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(never) => match never {},
+    }
+}
+
+/// Count the components of the input path against the input limits of `Limits`.
+#[derive(Debug)]
+pub(crate) struct LimitCounter {
+    max_input_length: usize,
+    max_components:   usize,
+    max_parent_dirs:  usize,
+    components:       usize,
+    parent_dirs:      usize,
+}
+
+impl Count for LimitCounter {
+    type Error = Error;
+
+    #[inline]
+    fn check_length(&mut self, length: usize) -> Result<(), Error> {
+        if length > self.max_input_length {
+            return Err(Error::LimitExceeded(Limit::InputLength));
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn count(&mut self, parent_dir: bool) -> Result<(), Error> {
+        self.components += 1;
+
+        if self.components > self.max_components {
+            return Err(Error::LimitExceeded(Limit::Components));
+        }
+
+        if parent_dir {
+            self.parent_dirs += 1;
+
+            if self.parent_dirs > self.max_parent_dirs {
+                return Err(Error::LimitExceeded(Limit::ParentDirs));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A limit in `Limits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The maximum length of the input path.
+    InputLength,
+    /// The maximum length of the output path.
+    OutputLength,
+    /// The maximum number of components of the input path.
+    Components,
+    /// The maximum number of `..` in the input path.
+    ParentDirs,
+}
+
+impl Display for Limit {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Limit::InputLength => f.write_str("the input path is too long"),
+            Limit::OutputLength => f.write_str("the output path is too long"),
+            Limit::Components => f.write_str("the input path has too many components"),
+            Limit::ParentDirs => f.write_str("the input path has too many `..`"),
+        }
+    }
+}
+
+/// Options of the engines. An option which does not make sense for an engine is ignored by it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    pub(crate) drive_cwds:              DriveCwds,
+    pub(crate) win32_normalization:     bool,
+    pub(crate) separator_style:         SeparatorStyle,
+    pub(crate) posix_double_slash_root: bool,
+    pub(crate) path_policy:             PathPolicy,
+    pub(crate) limits:                  Limits,
+}
+
+impl Options {
+    /// Create options with the default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the table of the per-drive current working directories, which the Windows engine uses to resolve drive-relative paths like `D:foo`.
+    ///
+    /// A drive without an entry is resolved from `cwd` if `cwd` is on the same drive, and from its root directory otherwise.
+    #[inline]
+    pub fn drive_cwds(mut self, drive_cwds: DriveCwds) -> Self {
+        self.drive_cwds = drive_cwds;
+
+        self
+    }
+
+    /// Set the current working directory of one drive. See `drive_cwds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `drive` is not an ASCII letter.
+    #[inline]
+    pub fn drive_cwd(mut self, drive: u8, cwd: impl Into<Vec<u8>>) -> Self {
+        self.drive_cwds.insert(drive, cwd);
+
+        self
+    }
+
+    /// Whether the Windows engine normalizes names the same way as Win32 does before opening a file, i.e., strips trailing dots and spaces. See `windows::normalize_win32`. The default value is `false`.
+    #[inline]
+    pub fn win32_normalization(mut self, win32_normalization: bool) -> Self {
+        self.win32_normalization = win32_normalization;
+
+        self
+    }
+
+    /// Set the separators in the paths returned by the Windows engine. Verbatim paths, which begin with `\\?\`, are never changed, because `/` is not a separator in them. The POSIX engine always uses `/`. The default value is `SeparatorStyle::Native`.
+    #[inline]
+    pub fn separator_style(mut self, separator_style: SeparatorStyle) -> Self {
+        self.separator_style = separator_style;
+
+        self
+    }
+
+    /// Whether the POSIX engine keeps a leading `//` as a root directory distinct from `/`, which POSIX allows to have an implementation-defined meaning, like `//host/share` on Cygwin. Three or more leading slashes still mean `/`. The default value is `false`, which collapses `//` to `/` like the Unix implementations of this crate.
+    #[inline]
+    pub fn posix_double_slash_root(mut self, posix_double_slash_root: bool) -> Self {
+        self.posix_double_slash_root = posix_double_slash_root;
+
+        self
+    }
+
+    /// Set the policy which `absolutize_virtually_with` of the engines checks the names of the input path with, before resolving it, so a name which a later `..` removes is checked too. Only the first violation is returned, as `Error::PolicyViolation`; the `policy_violations` function of the engines finds all of them. The default policy has no rules. See `policy::PathPolicy`.
+    #[inline]
+    pub fn path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+
+        self
+    }
+
+    /// Set the limits which `try_absolutize_from_with` and `absolutize_virtually_with` of the engines check. The default value has no limits. See `Limits`.
+    #[inline]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+
+        self
+    }
+}
+
+/// Errors returned by the engines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The path is not under the virtual root.
+    OutsideVirtualRoot,
+    /// A name in the path is not opened by Win32 as it is. `index` is the index of the component.
+    Win32Name { kind: Win32IssueKind, index: usize },
+    /// The path of an HTTP request is rejected.
+    RequestPath(RequestPathIssue),
+    /// A name in the path is rejected by a `PathPolicy`. `index` is the index of the component.
+    PolicyViolation { kind: PolicyViolationKind, index: usize },
+    /// The path exceeds a limit in `Limits`.
+    LimitExceeded(Limit),
+}
+
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::OutsideVirtualRoot => f.write_str("the path is not under the virtual root"),
+            Error::Win32Name {
+                kind,
+                index,
+            } => write!(f, "the component at index {} is not a valid Win32 name: {}", index, kind),
+            Error::RequestPath(issue) => write!(f, "the request path is rejected: {}", issue),
+            Error::PolicyViolation {
+                kind,
+                index,
+            } => write!(f, "the component at index {} is rejected by the policy: {}", index, kind),
+            Error::LimitExceeded(limit) => write!(f, "a limit is exceeded: {}", limit),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    #[inline]
+    fn from(error: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+    }
+}
@@ -0,0 +1,286 @@
+//! Validation of untrusted paths with composable policies.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use super::{windows::is_reserved_name, Error};
+
+/// A kind of names which a `PathPolicy` rejects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyViolationKind {
+    /// The name contains a NUL byte.
+    Nul,
+    /// The name contains a control character other than NUL, like `\n` or DEL.
+    ControlCharacter,
+    /// The name is longer than the maximum length.
+    ComponentTooLong,
+    /// The name is beyond the maximum number of names.
+    TooManyComponents,
+    /// The name begins with a dot, like `.git` or `.env`.
+    HiddenComponent,
+    /// The name is `..`.
+    ParentDir,
+    /// The name contains a character which Windows does not allow, one of `<>:"/\|?*`.
+    WindowsInvalidCharacter,
+    /// The name is a reserved DOS device name, like `CON` or `nul.txt`.
+    WindowsReservedName,
+    /// The name ends with a dot or a space, which Windows strips.
+    WindowsTrailingDotOrSpace,
+    /// The name contains a character outside the POSIX portable filename character set, `A-Z a-z 0-9 . _ -`, or begins with `-`.
+    NonPortableCharacter,
+}
+
+impl Display for PolicyViolationKind {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PolicyViolationKind::Nul => f.write_str("it contains a NUL byte"),
+            PolicyViolationKind::ControlCharacter => f.write_str("it contains a control character"),
+            PolicyViolationKind::ComponentTooLong => f.write_str("it is too long"),
+            PolicyViolationKind::TooManyComponents => f.write_str("there are too many components"),
+            PolicyViolationKind::HiddenComponent => f.write_str("it begins with a dot"),
+            PolicyViolationKind::ParentDir => f.write_str("it is `..`"),
+            PolicyViolationKind::WindowsInvalidCharacter => {
+                f.write_str("it contains a character which Windows does not allow")
+            },
+            PolicyViolationKind::WindowsReservedName => {
+                f.write_str("it is a reserved device name on Windows")
+            },
+            PolicyViolationKind::WindowsTrailingDotOrSpace => {
+                f.write_str("it ends with a dot or a space, which Windows strips")
+            },
+            PolicyViolationKind::NonPortableCharacter => {
+                f.write_str("it is not a portable filename")
+            },
+        }
+    }
+}
+
+/// A name in a path which a `PathPolicy` rejects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicyViolation<'a> {
+    /// Why the name is rejected.
+    pub kind:  PolicyViolationKind,
+    /// The index of the component, counted the same way as the `components` function of the engine does, including the prefix and the root directory.
+    pub index: usize,
+    /// The name.
+    pub name:  &'a [u8],
+}
+
+/// Rules which names in untrusted paths must follow. A new policy has no rules, and rules are added with the setters, so policies can be built on the presets and combined with `combine`.
+///
+/// The rules apply to the names in a path, i.e., not to the prefix, the root directory and `.`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathPolicy {
+    pub(crate) reject_nul:                bool,
+    pub(crate) reject_control_characters: bool,
+    pub(crate) max_component_length:      Option<usize>,
+    pub(crate) max_components:            Option<usize>,
+    pub(crate) reject_hidden_components:  bool,
+    pub(crate) reject_parent_dirs:        bool,
+    pub(crate) windows_compatible:        bool,
+    pub(crate) portable_characters:       bool,
+}
+
+impl PathPolicy {
+    /// Create a policy without rules.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A preset for names which follow POSIX strictly: no NUL bytes or control characters, only the portable filename character set, and at most 255 bytes per name.
+    #[inline]
+    pub fn strict_posix() -> Self {
+        Self::new()
+            .reject_nul(true)
+            .reject_control_characters(true)
+            .portable_characters(true)
+            .max_component_length(255)
+    }
+
+    /// A preset for names which are valid on every common file system: `strict_posix` plus Windows compatibility.
+    #[inline]
+    pub fn portable_filename() -> Self {
+        Self::strict_posix().windows_compatible(true)
+    }
+
+    /// A preset for paths of uploaded files: no NUL bytes, control characters, hidden names, `..` or names which Windows does not allow, at most 255 bytes per name and at most 32 names.
+    #[inline]
+    pub fn web_upload() -> Self {
+        Self::new()
+            .reject_nul(true)
+            .reject_control_characters(true)
+            .reject_hidden_components(true)
+            .reject_parent_dirs(true)
+            .windows_compatible(true)
+            .max_component_length(255)
+            .max_components(32)
+    }
+
+    /// Whether names with NUL bytes are rejected.
+    #[inline]
+    pub fn reject_nul(mut self, reject_nul: bool) -> Self {
+        self.reject_nul = reject_nul;
+
+        self
+    }
+
+    /// Whether names with control characters other than NUL, i.e., `0x01` to `0x1F` and `0x7F`, are rejected.
+    #[inline]
+    pub fn reject_control_characters(mut self, reject_control_characters: bool) -> Self {
+        self.reject_control_characters = reject_control_characters;
+
+        self
+    }
+
+    /// Set the maximum length of a name in bytes.
+    #[inline]
+    pub fn max_component_length(mut self, max_component_length: usize) -> Self {
+        self.max_component_length = Some(max_component_length);
+
+        self
+    }
+
+    /// Set the maximum number of names, counting `..`.
+    #[inline]
+    pub fn max_components(mut self, max_components: usize) -> Self {
+        self.max_components = Some(max_components);
+
+        self
+    }
+
+    /// Whether names which begin with a dot, like `.git` or `.env`, are rejected.
+    #[inline]
+    pub fn reject_hidden_components(mut self, reject_hidden_components: bool) -> Self {
+        self.reject_hidden_components = reject_hidden_components;
+
+        self
+    }
+
+    /// Whether `..` is rejected, even if it does not leave any directory.
+    #[inline]
+    pub fn reject_parent_dirs(mut self, reject_parent_dirs: bool) -> Self {
+        self.reject_parent_dirs = reject_parent_dirs;
+
+        self
+    }
+
+    /// Whether names which Windows does not allow are rejected, i.e., names with one of `<>:"/\|?*`, reserved device names and names which end with a dot or a space.
+    #[inline]
+    pub fn windows_compatible(mut self, windows_compatible: bool) -> Self {
+        self.windows_compatible = windows_compatible;
+
+        self
+    }
+
+    /// Whether names are restricted to the POSIX portable filename character set, `A-Z a-z 0-9 . _ -`, and must not begin with `-`.
+    #[inline]
+    pub fn portable_characters(mut self, portable_characters: bool) -> Self {
+        self.portable_characters = portable_characters;
+
+        self
+    }
+
+    /// Combine two policies into one which has the rules of both, taking the smaller one of each limit.
+    pub fn combine(self, other: &PathPolicy) -> Self {
+        let min = |a: Option<usize>, b: Option<usize>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => a.or(b),
+        };
+
+        PathPolicy {
+            reject_nul:                self.reject_nul || other.reject_nul,
+            reject_control_characters: self.reject_control_characters
+                || other.reject_control_characters,
+            max_component_length:      min(self.max_component_length, other.max_component_length),
+            max_components:            min(self.max_components, other.max_components),
+            reject_hidden_components:  self.reject_hidden_components
+                || other.reject_hidden_components,
+            reject_parent_dirs:        self.reject_parent_dirs || other.reject_parent_dirs,
+            windows_compatible:        self.windows_compatible || other.windows_compatible,
+            portable_characters:       self.portable_characters || other.portable_characters,
+        }
+    }
+
+    /// Check the `count`th name (counted from 1), which is the component at `index`, and push the violations.
+    pub(crate) fn check_name<'a>(
+        &self,
+        violations: &mut Vec<PolicyViolation<'a>>,
+        index: usize,
+        count: usize,
+        name: &'a [u8],
+        is_parent_dir: bool,
+    ) {
+        let mut push = |kind| {
+            violations.push(PolicyViolation {
+                kind,
+                index,
+                name,
+            })
+        };
+
+        if self.max_components.map(|max| count > max).unwrap_or(false) {
+            push(PolicyViolationKind::TooManyComponents);
+        }
+
+        if is_parent_dir {
+            if self.reject_parent_dirs {
+                push(PolicyViolationKind::ParentDir);
+            }
+
+            return;
+        }
+
+        if self.reject_nul && name.contains(&0) {
+            push(PolicyViolationKind::Nul);
+        }
+
+        if self.reject_control_characters
+            && name.iter().any(|&b| (1..0x20).contains(&b) || b == 0x7F)
+        {
+            push(PolicyViolationKind::ControlCharacter);
+        }
+
+        if self.max_component_length.map(|max| name.len() > max).unwrap_or(false) {
+            push(PolicyViolationKind::ComponentTooLong);
+        }
+
+        if self.reject_hidden_components && name.starts_with(b".") {
+            push(PolicyViolationKind::HiddenComponent);
+        }
+
+        if self.windows_compatible {
+            if name.iter().any(|b| b"<>:\"/\\|?*".contains(b)) {
+                push(PolicyViolationKind::WindowsInvalidCharacter);
+            }
+
+            if is_reserved_name(name) {
+                push(PolicyViolationKind::WindowsReservedName);
+            }
+
+            if name.ends_with(b".") || name.ends_with(b" ") {
+                push(PolicyViolationKind::WindowsTrailingDotOrSpace);
+            }
+        }
+
+        if self.portable_characters
+            && (name.starts_with(b"-")
+                || !name.iter().all(|&b| b.is_ascii_alphanumeric() || b"._-".contains(&b)))
+        {
+            push(PolicyViolationKind::NonPortableCharacter);
+        }
+    }
+}
+
+/// Reject a path by the first violation. See the `policy_violations` function of the engine.
+#[inline]
+pub(crate) fn first_violation(violations: &[PolicyViolation]) -> Result<(), Error> {
+    match violations.first() {
+        Some(violation) => Err(Error::PolicyViolation {
+            kind:  violation.kind,
+            index: violation.index,
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,670 @@
+//! The engine for POSIX paths, which are arbitrary bytes separated by `/`.
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use super::{
+    analyze::{self, AnalysisRules, AnomalyKind, Part, PathReport},
+    explain::{Step, Trace},
+    policy::{self, PathPolicy, PolicyViolation},
+    unwrap_infallible, Count, Error, Options,
+};
+
+/// The separator of POSIX paths.
+pub const SEPARATOR: u8 = b'/';
+
+const SEPARATOR_BYTES: &[u8] = b"/";
+
+const DOUBLE_SEPARATOR_BYTES: &[u8] = b"//";
+
+/// A component of a POSIX path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component<'a> {
+    /// The root directory, `/`.
+    RootDir,
+    /// A single dot at the start of a relative path.
+    CurDir,
+    /// A pair of dots.
+    ParentDir,
+    /// A normal file name.
+    Normal(&'a [u8]),
+}
+
+impl<'a> Component<'a> {
+    /// Get the bytes of this component.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            Component::RootDir => SEPARATOR_BYTES,
+            Component::CurDir => b".",
+            Component::ParentDir => b"..",
+            Component::Normal(name) => name,
+        }
+    }
+}
+
+/// An iterator over the components of a POSIX path. It splits paths the same way as `std::path::Path::components` does on Unix-like systems.
+#[derive(Debug, Clone)]
+pub struct Components<'a> {
+    path:    &'a [u8],
+    started: bool,
+}
+
+/// Iterate over the components of a POSIX path.
+#[inline]
+pub fn components(path: &[u8]) -> Components<'_> {
+    Components {
+        path,
+        started: false,
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = Component<'a>;
+
+    fn next(&mut self) -> Option<Component<'a>> {
+        if !self.started {
+            self.started = true;
+
+            match self.path {
+                [SEPARATOR, ..] => {
+                    self.path = &self.path[1..];
+
+                    return Some(Component::RootDir);
+                },
+                [b'.'] | [b'.', SEPARATOR, ..] => {
+                    self.path = &self.path[1..];
+
+                    return Some(Component::CurDir);
+                },
+                _ => (),
+            }
+        }
+
+        while !self.path.is_empty() {
+            let (name, rest) = match self.path.iter().position(|&b| b == SEPARATOR) {
+                Some(index) => (&self.path[..index], &self.path[index + 1..]),
+                None => (self.path, &self.path[self.path.len()..]),
+            };
+
+            self.path = rest;
+
+            match name {
+                b"" | b"." => (),
+                b".." => return Some(Component::ParentDir),
+                _ => return Some(Component::Normal(name)),
+            }
+        }
+
+        None
+    }
+}
+
+/// Whether the path starts with a root directory.
+#[inline]
+pub fn is_absolute(path: &[u8]) -> bool {
+    path.first() == Some(&SEPARATOR)
+}
+
+/// Get the root directory of an absolute path. It is `//` if the path begins with exactly two slashes and `double_slash` is `true`, and `/` otherwise.
+#[inline]
+fn root_token(path: &[u8], double_slash: bool) -> &'static [u8] {
+    if double_slash && path.starts_with(DOUBLE_SEPARATOR_BYTES) && path.get(2) != Some(&SEPARATOR) {
+        DOUBLE_SEPARATOR_BYTES
+    } else {
+        SEPARATOR_BYTES
+    }
+}
+
+/// Whether the path has only a root directory, like `/` or `//`.
+#[inline]
+fn is_root(path: &[u8]) -> bool {
+    let mut iter = components(path);
+
+    iter.next() == Some(Component::RootDir) && iter.next().is_none()
+}
+
+/// The components of the parent directory of the path, the same as `Path::parent().iter()`.
+fn parent_tokens(path: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut tokens: Vec<Component> = components(path).collect();
+
+    match tokens.pop() {
+        Some(Component::Normal(_)) | Some(Component::CurDir) | Some(Component::ParentDir) => {
+            Some(tokens.iter().map(Component::as_bytes).collect())
+        },
+        _ => None,
+    }
+}
+
+/// Whether `path` starts with `base`, compared component by component.
+pub fn starts_with(path: &[u8], base: &[u8]) -> bool {
+    let mut path = components(path);
+
+    for component in components(base) {
+        if path.next() != Some(component) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Append `path` to `buf` the same way as `PathBuf::push` does on Unix-like systems.
+pub fn push(buf: &mut Vec<u8>, path: &[u8]) {
+    if is_absolute(path) {
+        buf.clear();
+    } else if buf.last().map(|&b| b != SEPARATOR).unwrap_or(false) {
+        buf.push(SEPARATOR);
+    }
+
+    buf.extend_from_slice(path);
+}
+
+/// Join tokens with separators. `tokens` cannot be empty.
+fn join_tokens<'a>(
+    path: &'a [u8],
+    tokens: &[&[u8]],
+    first_is_root: bool,
+    has_change: bool,
+) -> Cow<'a, [u8]> {
+    let tokens_length = tokens.len();
+
+    debug_assert!(tokens_length > 0);
+
+    let mut size = tokens.iter().fold(tokens_length - 1, |acc, &x| acc + x.len());
+
+    if first_is_root && tokens_length > 1 {
+        size -= 1;
+    }
+
+    if has_change || size != path.len() {
+        let mut path_bytes = Vec::with_capacity(size);
+
+        let mut iter = tokens.iter();
+
+        path_bytes.extend_from_slice(iter.next().unwrap());
+
+        if tokens_length > 1 {
+            if !first_is_root {
+                path_bytes.push(SEPARATOR);
+            }
+
+            for token in iter.take(tokens_length - 2) {
+                path_bytes.extend_from_slice(token);
+
+                path_bytes.push(SEPARATOR);
+            }
+
+            path_bytes.extend_from_slice(tokens[tokens_length - 1]);
+        }
+
+        Cow::Owned(path_bytes)
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Push the tokens of `cwd`, or the tokens of the parent of `cwd` if `parent` is `true`, and return whether the first token is the root.
+fn push_cwd_tokens<'a>(
+    tokens: &mut Vec<&'a [u8]>,
+    cwd: &'a [u8],
+    parent: bool,
+    double_slash: bool,
+) -> bool {
+    let start = tokens.len();
+
+    if parent {
+        match parent_tokens(cwd) {
+            Some(cwd_parent) => tokens.extend(cwd_parent),
+            None => {
+                if is_root(cwd) {
+                    tokens.push(root_token(cwd, double_slash));
+
+                    return true;
+                } else {
+                    return false;
+                }
+            },
+        }
+    } else {
+        tokens.extend(components(cwd).map(|c| c.as_bytes()));
+    }
+
+    if tokens.len() > start && tokens[start] == SEPARATOR_BYTES {
+        tokens[start] = root_token(cwd, double_slash);
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Get an absolute path. `cwd` is the current working directory which relative paths are resolved from.
+///
+/// A leading `//` is collapsed to `/`. See `Options::posix_double_slash_root` to keep it.
+#[inline]
+pub fn absolutize_from<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    absolutize_from_with(path, cwd, &Options::new())
+}
+
+/// Get an absolute path with options. See `absolutize_from`.
+///
+/// The limits in `options` are not checked. Use `try_absolutize_from_with` to check them.
+#[inline]
+pub fn absolutize_from_with<'a>(path: &'a [u8], cwd: &[u8], options: &Options) -> Cow<'a, [u8]> {
+    unwrap_infallible(absolutize_traced(path, cwd, options, &mut (), &mut ()))
+}
+
+/// Get an absolute path with options, and the steps taken to get it. See `absolutize_from` and `explain::Step`.
+pub fn explain_from_with<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+) -> (Cow<'a, [u8]>, Vec<Step>) {
+    let mut steps = Vec::new();
+
+    let path = unwrap_infallible(absolutize_traced(path, cwd, options, &mut (), &mut steps));
+
+    (path, steps)
+}
+
+fn absolutize_traced<'a, C: Count>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut C,
+    trace: &mut impl Trace,
+) -> Result<Cow<'a, [u8]>, C::Error> {
+    counter.check_length(path.len())?;
+
+    let double_slash = options.posix_double_slash_root;
+
+    let mut iter = components(path);
+
+    let mut has_change = false;
+
+    if let Some(first_component) = iter.next() {
+        counter.count(first_component == Component::ParentDir)?;
+
+        let mut tokens = Vec::new();
+
+        let first_is_root = match first_component {
+            Component::RootDir => {
+                let root = root_token(path, double_slash);
+
+                trace.record(|| Step::Root {
+                    root: root.to_vec()
+                });
+
+                tokens.push(root);
+
+                true
+            },
+            Component::CurDir => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: false
+                });
+                trace.record(|| Step::CurDirRemoved);
+
+                push_cwd_tokens(&mut tokens, cwd, false, double_slash)
+            },
+            Component::ParentDir => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: true
+                });
+
+                push_cwd_tokens(&mut tokens, cwd, true, double_slash)
+            },
+            Component::Normal(name) => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: false
+                });
+
+                let first_is_root = push_cwd_tokens(&mut tokens, cwd, false, double_slash);
+
+                trace.record(|| Step::Kept {
+                    name: name.to_vec()
+                });
+
+                tokens.push(name);
+
+                first_is_root
+            },
+        };
+
+        for component in iter {
+            counter.count(component == Component::ParentDir)?;
+
+            match component {
+                Component::CurDir => {
+                    // may be unreachable
+                    trace.record(|| Step::CurDirRemoved);
+
+                    has_change = true;
+                },
+                Component::ParentDir => {
+                    let tokens_length = tokens.len();
+
+                    if tokens_length > 0 && (tokens_length != 1 || !first_is_root) {
+                        let name = tokens.remove(tokens_length - 1);
+
+                        trace.record(|| Step::ParentDirRemoved {
+                            name: name.to_vec()
+                        });
+                    } else {
+                        trace.record(|| Step::ParentDirClamped);
+                    }
+
+                    has_change = true;
+                },
+                _ => {
+                    trace.record(|| Step::Kept {
+                        name: component.as_bytes().to_vec()
+                    });
+
+                    tokens.push(component.as_bytes());
+                },
+            }
+        }
+
+        if tokens.is_empty() {
+            // `cwd` is empty and every component has been removed
+            return Ok(Cow::Owned(Vec::new()));
+        }
+
+        Ok(join_tokens(path, &tokens, first_is_root, has_change))
+    } else {
+        trace.record(|| Step::Empty);
+
+        Ok(Cow::Owned(cwd.to_vec()))
+    }
+}
+
+/// Get an absolute path with options, checking the limits in `options`. See `absolutize_from` and `Limits`.
+pub fn try_absolutize_from_with<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let path = absolutize_traced(path, cwd, options, &mut options.limits.counter(), &mut ())?;
+
+    options.limits.check_output(path.len())?;
+
+    Ok(path)
+}
+
+/// Remove the dots in a path. Only a leading single dot or a leading pair of dots is resolved from `cwd`, so a relative path stays relative otherwise.
+#[inline]
+pub fn parse_dot_from<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    parse_dot_from_with(path, cwd, &Options::new())
+}
+
+/// Remove the dots in a path with options. See `parse_dot_from`.
+#[inline]
+pub fn parse_dot_from_with<'a>(path: &'a [u8], cwd: &[u8], options: &Options) -> Cow<'a, [u8]> {
+    unwrap_infallible(parse_dot_counted(path, cwd, options, &mut ()))
+}
+
+fn parse_dot_counted<'a, C: Count>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut C,
+) -> Result<Cow<'a, [u8]>, C::Error> {
+    counter.check_length(path.len())?;
+
+    let double_slash = options.posix_double_slash_root;
+
+    let mut iter = components(path);
+
+    let mut has_dots = false;
+
+    if let Some(first_component) = iter.next() {
+        counter.count(first_component == Component::ParentDir)?;
+
+        let mut tokens = Vec::new();
+
+        let first_is_root = match first_component {
+            Component::RootDir => {
+                tokens.push(root_token(path, double_slash));
+
+                true
+            },
+            Component::CurDir => {
+                has_dots = true;
+
+                push_cwd_tokens(&mut tokens, cwd, false, double_slash)
+            },
+            Component::ParentDir => {
+                has_dots = true;
+
+                push_cwd_tokens(&mut tokens, cwd, true, double_slash)
+            },
+            Component::Normal(name) => {
+                tokens.push(name);
+
+                false
+            },
+        };
+
+        for component in iter {
+            counter.count(component == Component::ParentDir)?;
+
+            match component {
+                Component::CurDir => {
+                    // may be unreachable
+                    has_dots = true;
+                },
+                Component::ParentDir => {
+                    let tokens_length = tokens.len();
+
+                    if tokens_length > 0 && (tokens_length != 1 || !first_is_root) {
+                        tokens.remove(tokens_length - 1);
+                    }
+
+                    has_dots = true;
+                },
+                _ => {
+                    tokens.push(component.as_bytes());
+                },
+            }
+        }
+
+        if tokens.is_empty() {
+            return Ok(Cow::Owned(Vec::new()));
+        }
+
+        Ok(join_tokens(path, &tokens, first_is_root, has_dots))
+    } else {
+        Ok(Cow::Borrowed(path))
+    }
+}
+
+/// Find the anomalies in a path without rewriting it, i.e., redundant separators, `.`, `..` which climbs out of the base directory, trailing separators, and a root directory if `rules` expects a relative path. The spans are byte ranges of `path`.
+#[inline]
+pub fn analyze(path: &[u8], rules: &AnalysisRules) -> PathReport {
+    analyze_with(path, rules, &Options::new())
+}
+
+/// Find the anomalies in a path with options. See `analyze`.
+///
+/// If `Options::posix_double_slash_root` is `true`, a leading `//` is a root directory, so its second slash is not a redundant separator.
+pub fn analyze_with(path: &[u8], rules: &AnalysisRules, options: &Options) -> PathReport {
+    let mut report = PathReport::default();
+
+    if rules.expect_relative && is_absolute(path) {
+        let root_end = path.iter().take_while(|&&b| b == SEPARATOR).count();
+
+        report.push(AnomalyKind::NotRelative, 0..root_end);
+    }
+
+    let root_length = root_token(path, options.posix_double_slash_root).len();
+
+    let is_separator = |b| b == SEPARATOR;
+
+    let mut iter = components(path);
+
+    let parts = core::iter::from_fn(|| {
+        let component = iter.next()?;
+
+        let rest_length = iter.path.len();
+
+        Some(match component {
+            Component::RootDir => (Part::RootDir, 0..root_length),
+            Component::CurDir => {
+                (Part::CurDir, analyze::name_span(path, rest_length, 1, is_separator))
+            },
+            Component::ParentDir => {
+                (Part::ParentDir, analyze::name_span(path, rest_length, 2, is_separator))
+            },
+            Component::Normal(name) => {
+                (Part::Normal, analyze::name_span(path, rest_length, name.len(), is_separator))
+            },
+        })
+    });
+
+    analyze::report_parts(&mut report, path, 0, parts, is_separator);
+
+    report.finish()
+}
+
+/// Find the names in a path which `policy` rejects. A name can have several violations.
+pub fn policy_violations<'a>(path: &'a [u8], policy: &PathPolicy) -> Vec<PolicyViolation<'a>> {
+    let mut violations = Vec::new();
+
+    let mut count = 0;
+
+    for (index, component) in components(path).enumerate() {
+        match component {
+            Component::Normal(name) => {
+                count += 1;
+
+                policy.check_name(&mut violations, index, count, name, false);
+            },
+            Component::ParentDir => {
+                count += 1;
+
+                policy.check_name(&mut violations, index, count, b"..", true);
+            },
+            _ => (),
+        }
+    }
+
+    violations
+}
+
+/// Get an absolute path **only under a specific directory**. `cwd` is the current working directory which `virtual_root` and a leading dot of `path` are resolved from.
+#[inline]
+pub fn absolutize_virtually<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    absolutize_virtually_with(path, virtual_root, cwd, &Options::new())
+}
+
+/// Get an absolute path **only under a specific directory** with options. See `absolutize_virtually`.
+///
+/// If `Options::posix_double_slash_root` is `true`, `//host` and `/host` are different directories, so neither of them is under the other.
+pub fn absolutize_virtually_with<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let double_slash = options.posix_double_slash_root;
+
+    if options.path_policy != PathPolicy::new() {
+        policy::first_violation(&policy_violations(path, &options.path_policy))?;
+    }
+
+    let path = parse_dot_counted(path, cwd, options, &mut options.limits.counter())?;
+
+    let virtual_root = absolutize_from_with(virtual_root, cwd, options);
+
+    if is_absolute(&path) {
+        if root_token(&path, double_slash) != root_token(&virtual_root, double_slash)
+            || !starts_with(&path, &virtual_root)
+        {
+            return Err(Error::OutsideVirtualRoot);
+        }
+
+        options.limits.check_output(path.len())?;
+
+        Ok(path)
+    } else {
+        let mut virtual_root = virtual_root.into_owned();
+
+        // `push` appends a separator even if the path is empty
+        if !path.is_empty() {
+            push(&mut virtual_root, &path);
+        }
+
+        options.limits.check_output(virtual_root.len())?;
+
+        Ok(Cow::Owned(virtual_root))
+    }
+}
+
+/// Get the path relative to `virtual_root` with a leading `/`, i.e., `/srv/root/a/b` under `/srv/root` becomes `/a/b`. This is the inverse of `absolutize_virtually`. Both paths are absolutized from `cwd` first.
+#[inline]
+pub fn virtualize<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    virtualize_with(path, virtual_root, cwd, &Options::new())
+}
+
+/// Get the path relative to `virtual_root` with a leading `/` with options. See `virtualize`.
+pub fn virtualize_with<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let double_slash = options.posix_double_slash_root;
+
+    let virtual_root = absolutize_from_with(virtual_root, cwd, options);
+
+    let path = absolutize_from_with(path, cwd, options);
+
+    if root_token(&path, double_slash) != root_token(&virtual_root, double_slash)
+        || !starts_with(&path, &virtual_root)
+    {
+        return Err(Error::OutsideVirtualRoot);
+    }
+
+    let mut rest = components(&path);
+
+    let mut root_length = 0;
+
+    for _ in components(&virtual_root) {
+        rest.next();
+
+        root_length += 1;
+    }
+
+    if root_length <= 1 {
+        // the virtual root is the root directory
+        return Ok(path);
+    }
+
+    let mut virtualized = Vec::with_capacity(path.len());
+
+    for component in rest {
+        virtualized.push(SEPARATOR);
+        virtualized.extend_from_slice(component.as_bytes());
+    }
+
+    if virtualized.is_empty() {
+        virtualized.push(SEPARATOR);
+    }
+
+    Ok(Cow::Owned(virtualized))
+}
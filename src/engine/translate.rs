@@ -0,0 +1,121 @@
+//! Translation between Windows absolute paths and the POSIX-style paths used by MSYS2 (Git Bash), Cygwin and WSL, like `C:\Users\x`, `/c/Users/x`, `/cygdrive/c/Users/x` and `/mnt/c/Users/x`.
+
+use alloc::vec::Vec;
+
+use super::{
+    posix,
+    windows::{self, Prefix},
+};
+
+/// Where a POSIX environment on Windows mounts the drives, like `/cygdrive/` for `/cygdrive/c`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DriveMount {
+    prefix: Vec<u8>,
+}
+
+impl DriveMount {
+    /// Create a mount prefix. Leading and trailing slashes are added if missing, so `mnt` means `/mnt/`, and an empty prefix means `/`.
+    pub fn new(prefix: impl AsRef<[u8]>) -> Self {
+        let prefix = prefix.as_ref();
+
+        let mut normalized = Vec::with_capacity(prefix.len() + 2);
+
+        for name in prefix.split(|&b| b == posix::SEPARATOR).filter(|name| !name.is_empty()) {
+            normalized.push(posix::SEPARATOR);
+            normalized.extend_from_slice(name);
+        }
+
+        normalized.push(posix::SEPARATOR);
+
+        DriveMount {
+            prefix: normalized
+        }
+    }
+
+    /// The mount prefix of MSYS2 and Git Bash, `/`, as in `/c/Users/x`.
+    #[inline]
+    pub fn msys2() -> Self {
+        Self::new("/")
+    }
+
+    /// The default mount prefix of Cygwin, `/cygdrive/`, as in `/cygdrive/c/Users/x`.
+    #[inline]
+    pub fn cygwin() -> Self {
+        Self::new("/cygdrive/")
+    }
+
+    /// The default mount prefix of WSL, `/mnt/`, as in `/mnt/c/Users/x`.
+    #[inline]
+    pub fn wsl() -> Self {
+        Self::new("/mnt/")
+    }
+
+    /// Get the mount prefix, which begins and ends with `/`.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.prefix
+    }
+}
+
+/// Translate a POSIX-style path like `/c/Users/x` into a Windows absolute path like `C:\Users\x`. The path is absolutized from `cwd`, which is a POSIX-style path, too, before it is translated.
+///
+/// Returns `None` if the absolute path is not on a drive mounted under `mount`, or has a name containing `\`, which would become a separator.
+pub fn posix_to_windows(path: &[u8], cwd: &[u8], mount: &DriveMount) -> Option<Vec<u8>> {
+    let path = posix::absolutize_from(path, cwd);
+
+    let rest = path.strip_prefix(mount.as_bytes())?;
+
+    let (drive, rest) = match rest {
+        [drive] => (*drive, &rest[1..]),
+        [drive, posix::SEPARATOR, ..] => (*drive, &rest[2..]),
+        _ => return None,
+    };
+
+    if !drive.is_ascii_alphabetic() || rest.contains(&windows::SEPARATOR) {
+        return None;
+    }
+
+    let mut translated = Vec::with_capacity(rest.len() + 3);
+
+    translated.push(drive.to_ascii_uppercase());
+    translated.extend_from_slice(b":\\");
+    translated
+        .extend(rest.iter().map(|&b| if b == posix::SEPARATOR { windows::SEPARATOR } else { b }));
+
+    Some(translated)
+}
+
+/// Translate a Windows path like `C:\Users\x` into a POSIX-style path like `/c/Users/x`. The path is absolutized from `cwd`, which is a Windows path, too, before it is translated. A verbatim path like `\\?\C:\Users\x` is translated if it can be simplified (see `windows::simplify_verbatim`).
+///
+/// Returns `None` if the absolute path is not on a drive, like `\\server\share\x`.
+pub fn windows_to_posix(path: &[u8], cwd: &[u8], mount: &DriveMount) -> Option<Vec<u8>> {
+    let path = windows::absolutize_from(path, cwd);
+
+    let path = windows::simplify_verbatim(&path);
+
+    let drive = match windows::parse_prefix(&path) {
+        Some(Prefix::Disk(drive)) if windows::has_root(&path) => drive,
+        _ => return None,
+    };
+
+    // skip `C:\`
+    let rest = &path[3..];
+
+    let mut translated = Vec::with_capacity(mount.as_bytes().len() + rest.len() + 2);
+
+    translated.extend_from_slice(mount.as_bytes());
+    translated.push(drive.to_ascii_lowercase());
+
+    if !rest.is_empty() {
+        translated.push(posix::SEPARATOR);
+        translated.extend(rest.iter().map(|&b| {
+            if windows::is_separator(b) {
+                posix::SEPARATOR
+            } else {
+                b
+            }
+        }));
+    }
+
+    Some(translated)
+}
@@ -0,0 +1,56 @@
+use alloc::vec::Vec;
+
+/// The current working directories of drives.
+///
+/// Windows keeps a separate current directory for each drive, in the hidden `=C:`, `=D:`, ... environment variables. A drive-relative path like `D:foo` is resolved from the current directory of its own drive, not from the current directory of the process.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriveCwds {
+    cwds: [Option<Vec<u8>>; 26],
+}
+
+#[inline]
+fn drive_index(drive: u8) -> Option<usize> {
+    if drive.is_ascii_alphabetic() {
+        Some((drive.to_ascii_uppercase() - b'A') as usize)
+    } else {
+        None
+    }
+}
+
+impl DriveCwds {
+    /// Create an empty table.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the current working directory of a drive, like `b'D'` and `br"D:\foo"`. The drive letter is case-insensitive. Returns the old one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `drive` is not an ASCII letter.
+    #[inline]
+    pub fn insert(&mut self, drive: u8, cwd: impl Into<Vec<u8>>) -> Option<Vec<u8>> {
+        let index = drive_index(drive).expect("a drive letter must be an ASCII letter");
+
+        self.cwds[index].replace(cwd.into())
+    }
+
+    /// Remove the current working directory of a drive. Returns the old one.
+    #[inline]
+    pub fn remove(&mut self, drive: u8) -> Option<Vec<u8>> {
+        drive_index(drive).and_then(|index| self.cwds[index].take())
+    }
+
+    /// Get the current working directory of a drive.
+    #[inline]
+    pub fn get(&self, drive: u8) -> Option<&[u8]> {
+        drive_index(drive).and_then(|index| self.cwds[index].as_deref())
+    }
+
+    /// Whether no current working directory is set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cwds.iter().all(Option::is_none)
+    }
+}
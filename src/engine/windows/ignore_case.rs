@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+
+use super::{components, parse_prefix, wtf8, Component, Prefix};
+
+/// Map a code point to uppercase with `char::to_uppercase`, restricted to code points in the Basic Multilingual Plane which have a one-to-one uppercase mapping, so surrogates, supplementary code points and characters like `ß` are kept.
+///
+/// This approximates the `$UpCase` table of NTFS but is not the same. The table is written when a volume is formatted, from an older version of Unicode, so characters whose mappings were added later, e.g. Georgian `U+10D0` to `U+1C90` since Unicode 11, are upcased here but not by NTFS.
+#[inline]
+fn upcase(code_point: u32) -> u32 {
+    if code_point < 0x80 {
+        return (code_point as u8).to_ascii_uppercase() as u32;
+    }
+
+    if code_point > 0xFFFF {
+        return code_point;
+    }
+
+    match char::from_u32(code_point) {
+        Some(c) => {
+            let mut upper = c.to_uppercase();
+
+            match (upper.next(), upper.next()) {
+                (Some(u), None) if (u as u32) <= 0xFFFF => u as u32,
+                _ => code_point,
+            }
+        },
+        None => code_point,
+    }
+}
+
+/// Whether two WTF-8 names are equal, ignoring case with simple uppercase mappings which approximate NTFS.
+fn eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let mut i = 0;
+    let mut j = 0;
+
+    loop {
+        match (wtf8::next_code_point(a, &mut i), wtf8::next_code_point(b, &mut j)) {
+            (Some(x), Some(y)) => {
+                if x != y && upcase(x) != upcase(y) {
+                    return false;
+                }
+            },
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Whether two prefixes are equal, ignoring case with simple uppercase mappings which approximate NTFS.
+pub(super) fn prefix_eq_ignore_case(a: Prefix, b: Prefix) -> bool {
+    match (a, b) {
+        (Prefix::Verbatim(x), Prefix::Verbatim(y)) | (Prefix::DeviceNS(x), Prefix::DeviceNS(y)) => {
+            eq_ignore_case(x, y)
+        },
+        (Prefix::VerbatimUNC(x1, y1), Prefix::VerbatimUNC(x2, y2))
+        | (Prefix::UNC(x1, y1), Prefix::UNC(x2, y2)) => {
+            eq_ignore_case(x1, x2) && eq_ignore_case(y1, y2)
+        },
+        // the drive letters have been uppercased
+        (Prefix::VerbatimDisk(x), Prefix::VerbatimDisk(y)) | (Prefix::Disk(x), Prefix::Disk(y)) => {
+            x == y
+        },
+        _ => false,
+    }
+}
+
+fn component_eq_ignore_case(a: Component, b: Component) -> bool {
+    match (a, b) {
+        (Component::Prefix(x), Component::Prefix(y)) => prefix_eq_ignore_case(x.kind(), y.kind()),
+        (Component::Normal(x), Component::Normal(y)) => eq_ignore_case(x, y),
+        _ => a == b,
+    }
+}
+
+/// Whether two paths are equal component by component, ignoring case with simple uppercase mappings which approximate NTFS.
+///
+/// Like `std::path::Path`'s comparison, `\` and `/` are equal, repeated separators and non-leading `.` are ignored, and `..` is not resolved. The paths are WTF-8, so every Windows path can be compared.
+pub fn paths_equal_ignore_case(a: &[u8], b: &[u8]) -> bool {
+    let mut a = components(a);
+    let mut b = components(b);
+
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => {
+                if !component_eq_ignore_case(x, y) {
+                    return false;
+                }
+            },
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Whether `base` is a leading part of `path`, compared component by component and ignoring case with simple uppercase mappings which approximate NTFS. So `C:\Root\a` starts with `c:\root`, but `C:\root2` does not start with `C:\root`. See `paths_equal_ignore_case`.
+pub fn starts_with_ignore_case(path: &[u8], base: &[u8]) -> bool {
+    let mut path = components(path);
+
+    for component in components(base) {
+        match path.next() {
+            Some(c) if component_eq_ignore_case(c, component) => (),
+            _ => return false,
+        }
+    }
+
+    true
+}
+
+/// Get a key of a path for hashing and ordering which ignores case, i.e., every code point is mapped to uppercase with simple uppercase mappings which approximate NTFS, and `/` is replaced with `\` except in verbatim paths, where `/` is not a separator. Absolutized paths which are equal by `paths_equal_ignore_case` have the same key.
+pub fn case_fold_key(path: &[u8]) -> Vec<u8> {
+    let verbatim = parse_prefix(path).map(|prefix| prefix.is_verbatim()).unwrap_or(false);
+
+    let mut key = Vec::with_capacity(path.len());
+
+    let mut i = 0;
+
+    while let Some(code_point) = wtf8::next_code_point(path, &mut i) {
+        if code_point == b'/' as u32 && !verbatim {
+            key.push(b'\\');
+        } else {
+            wtf8::push_code_point(&mut key, upcase(code_point));
+        }
+    }
+
+    key
+}
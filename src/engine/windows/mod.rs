@@ -0,0 +1,1388 @@
+//! The engine for Windows paths, which are WTF-8 bytes separated by `\` or `/`.
+//!
+//! The paths are parsed the same way as `std::path::Path` parses them on Windows, but this module works on every platform.
+
+mod drive_cwds;
+mod ignore_case;
+mod verbatim;
+mod win32;
+pub mod wtf8;
+
+use alloc::{borrow::Cow, vec::Vec};
+
+pub use drive_cwds::DriveCwds;
+pub use ignore_case::{case_fold_key, paths_equal_ignore_case, starts_with_ignore_case};
+pub use verbatim::{simplify_verbatim, to_verbatim_from, to_verbatim_from_with, MAX_PATH};
+pub(crate) use win32::is_reserved_name;
+pub use win32::{normalize_win32, validate_win32, win32_issues, Win32Issue, Win32IssueKind};
+
+use super::{
+    analyze::{self, AnalysisRules, AnomalyKind, Part, PathReport},
+    explain::{PrefixHandling, Step, Trace},
+    policy::{self, PathPolicy, PolicyViolation},
+    unwrap_infallible, Count, Error, LimitCounter, Options, SeparatorStyle,
+};
+
+/// The main separator of Windows paths.
+pub const SEPARATOR: u8 = b'\\';
+
+const SEPARATOR_BYTES: &[u8] = b"\\";
+
+/// Whether the byte is a separator, `\` or `/`.
+#[inline]
+pub fn is_separator(b: u8) -> bool {
+    b == b'\\' || b == b'/'
+}
+
+/// Verbatim paths can only be separated by `\`.
+#[inline]
+fn is_verbatim_separator(b: u8) -> bool {
+    b == b'\\'
+}
+
+/// A prefix of a Windows path, the same as `std::path::Prefix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefix<'a> {
+    /// `\\?\prefix`
+    Verbatim(&'a [u8]),
+    /// `\\?\UNC\server\share`
+    VerbatimUNC(&'a [u8], &'a [u8]),
+    /// `\\?\C:`, with the uppercase drive letter.
+    VerbatimDisk(u8),
+    /// `\\.\device`
+    DeviceNS(&'a [u8]),
+    /// `\\server\share`
+    UNC(&'a [u8], &'a [u8]),
+    /// `C:`, with the uppercase drive letter.
+    Disk(u8),
+}
+
+impl<'a> Prefix<'a> {
+    #[inline]
+    fn len(&self) -> usize {
+        fn share_len(share: &[u8]) -> usize {
+            if share.is_empty() {
+                0
+            } else {
+                1 + share.len()
+            }
+        }
+
+        match self {
+            Prefix::Verbatim(x) => 4 + x.len(),
+            Prefix::VerbatimUNC(x, y) => 8 + x.len() + share_len(y),
+            Prefix::VerbatimDisk(_) => 6,
+            Prefix::UNC(x, y) => 2 + x.len() + share_len(y),
+            Prefix::DeviceNS(x) => 4 + x.len(),
+            Prefix::Disk(_) => 2,
+        }
+    }
+
+    /// Whether the prefix is verbatim, i.e., begins with `\\?\`.
+    #[inline]
+    pub fn is_verbatim(&self) -> bool {
+        matches!(self, Prefix::Verbatim(_) | Prefix::VerbatimUNC(..) | Prefix::VerbatimDisk(_))
+    }
+
+    #[inline]
+    fn is_drive(&self) -> bool {
+        matches!(self, Prefix::Disk(_))
+    }
+
+    #[inline]
+    fn has_implicit_root(&self) -> bool {
+        !self.is_drive()
+    }
+}
+
+/// A prefix and its raw bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct PrefixComponent<'a> {
+    raw:    &'a [u8],
+    parsed: Prefix<'a>,
+}
+
+impl<'a> PrefixComponent<'a> {
+    /// Get the parsed prefix.
+    #[inline]
+    pub fn kind(&self) -> Prefix<'a> {
+        self.parsed
+    }
+
+    /// Get the raw bytes of the prefix.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.raw
+    }
+}
+
+impl<'a> PartialEq for PrefixComponent<'a> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.parsed == other.parsed
+    }
+}
+
+impl<'a> Eq for PrefixComponent<'a> {}
+
+/// A component of a Windows path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component<'a> {
+    /// A prefix like `C:` or `\\server\share`.
+    Prefix(PrefixComponent<'a>),
+    /// The root directory, `\`.
+    RootDir,
+    /// A single dot at the start of a relative path, or any single dot in a verbatim path.
+    CurDir,
+    /// A pair of dots.
+    ParentDir,
+    /// A normal file name.
+    Normal(&'a [u8]),
+}
+
+impl<'a> Component<'a> {
+    /// Get the bytes of this component. The root directory is always `\`.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            Component::Prefix(prefix) => prefix.as_bytes(),
+            Component::RootDir => SEPARATOR_BYTES,
+            Component::CurDir => b".",
+            Component::ParentDir => b"..",
+            Component::Normal(name) => name,
+        }
+    }
+}
+
+/// Split `path` at the first separator.
+fn parse_next_component(path: &[u8], verbatim: bool) -> (&[u8], &[u8]) {
+    let position = if verbatim {
+        path.iter().position(|&b| is_verbatim_separator(b))
+    } else {
+        path.iter().position(|&b| is_separator(b))
+    };
+
+    match position {
+        Some(index) => (&path[..index], &path[index + 1..]),
+        None => (path, &path[path.len()..]),
+    }
+}
+
+#[inline]
+fn parse_drive(path: &[u8]) -> Option<u8> {
+    match path {
+        [drive, b':', ..] if drive.is_ascii_alphabetic() => Some(drive.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+#[inline]
+fn parse_drive_exact(path: &[u8]) -> Option<u8> {
+    if path.get(2).map(|&b| is_verbatim_separator(b)).unwrap_or(true) {
+        parse_drive(path)
+    } else {
+        None
+    }
+}
+
+/// Parse the prefix of a path.
+pub fn parse_prefix(path: &[u8]) -> Option<Prefix<'_>> {
+    // the first 8 bytes are matched with `/` treated as `\`
+    let mut normalized = [0u8; 8];
+
+    let normalized_length = path.len().min(normalized.len());
+
+    for (n, &b) in normalized.iter_mut().zip(&path[..normalized_length]) {
+        *n = if b == b'/' { b'\\' } else { b };
+    }
+
+    let normalized = &normalized[..normalized_length];
+
+    if normalized.starts_with(br"\\") {
+        // the meaning of verbatim paths can change when they use a different separator
+        if normalized[2..].starts_with(br"?\") && !path[..4].contains(&b'/') {
+            if normalized[4..].starts_with(br"UNC\") {
+                let (server, path) = parse_next_component(&path[8..], true);
+                let (share, _) = parse_next_component(path, true);
+
+                Some(Prefix::VerbatimUNC(server, share))
+            } else {
+                let path = &path[4..];
+
+                match parse_drive_exact(path) {
+                    Some(drive) => Some(Prefix::VerbatimDisk(drive)),
+                    None => Some(Prefix::Verbatim(parse_next_component(path, true).0)),
+                }
+            }
+        } else if normalized[2..].starts_with(br".\") {
+            Some(Prefix::DeviceNS(parse_next_component(&path[4..], false).0))
+        } else {
+            let (server, path) = parse_next_component(&path[2..], false);
+            let (share, _) = parse_next_component(path, false);
+
+            if !server.is_empty() && !share.is_empty() {
+                Some(Prefix::UNC(server, share))
+            } else {
+                None
+            }
+        }
+    } else {
+        parse_drive(path).map(Prefix::Disk)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Prefix,
+    StartDir,
+    Body,
+}
+
+/// An iterator over the components of a Windows path. It splits paths the same way as `std::path::Path::components` does on Windows.
+#[derive(Debug, Clone)]
+pub struct Components<'a> {
+    path:              &'a [u8],
+    prefix:            Option<Prefix<'a>>,
+    has_physical_root: bool,
+    state:             State,
+}
+
+/// Iterate over the components of a Windows path.
+pub fn components(path: &[u8]) -> Components<'_> {
+    let prefix = parse_prefix(path);
+
+    let has_physical_root = {
+        let path = &path[prefix.map(|p| p.len()).unwrap_or(0)..];
+
+        !path.is_empty() && is_separator(path[0])
+    };
+
+    Components {
+        path,
+        prefix,
+        has_physical_root,
+        state: State::Prefix,
+    }
+}
+
+impl<'a> Components<'a> {
+    #[inline]
+    fn prefix_verbatim(&self) -> bool {
+        self.prefix.map(|p| p.is_verbatim()).unwrap_or(false)
+    }
+
+    #[inline]
+    fn has_root(&self) -> bool {
+        self.has_physical_root || self.prefix.map(|p| p.has_implicit_root()).unwrap_or(false)
+    }
+
+    #[inline]
+    fn include_cur_dir(&self) -> bool {
+        if self.has_root() {
+            return false;
+        }
+
+        match self.path {
+            [b'.'] => true,
+            [b'.', b, ..] => is_separator(*b),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = Component<'a>;
+
+    fn next(&mut self) -> Option<Component<'a>> {
+        if self.state == State::Prefix {
+            self.state = State::StartDir;
+
+            if let Some(prefix) = self.prefix {
+                let length = prefix.len();
+
+                let raw = &self.path[..length];
+
+                self.path = &self.path[length..];
+
+                return Some(Component::Prefix(PrefixComponent {
+                    raw,
+                    parsed: prefix,
+                }));
+            }
+        }
+
+        if self.state == State::StartDir {
+            self.state = State::Body;
+
+            if self.has_physical_root {
+                self.path = &self.path[1..];
+
+                return Some(Component::RootDir);
+            } else if let Some(prefix) = self.prefix {
+                if prefix.has_implicit_root() && !prefix.is_verbatim() {
+                    return Some(Component::RootDir);
+                }
+            } else if self.include_cur_dir() {
+                self.path = &self.path[1..];
+
+                return Some(Component::CurDir);
+            }
+        }
+
+        let verbatim = self.prefix_verbatim();
+
+        while !self.path.is_empty() {
+            let (name, rest) = parse_next_component(self.path, verbatim);
+
+            self.path = rest;
+
+            match name {
+                b"." if verbatim => return Some(Component::CurDir),
+                b"" | b"." => (),
+                b".." => return Some(Component::ParentDir),
+                _ => return Some(Component::Normal(name)),
+            }
+        }
+
+        None
+    }
+}
+
+/// Get the prefix of a path.
+#[inline]
+pub fn get_path_prefix(path: &[u8]) -> Option<PrefixComponent<'_>> {
+    match components(path).next() {
+        Some(Component::Prefix(prefix)) => Some(prefix),
+        _ => None,
+    }
+}
+
+/// Whether the path has a root, either a separator after the prefix or implied by a prefix like `\\server\share`.
+#[inline]
+pub fn has_root(path: &[u8]) -> bool {
+    components(path).has_root()
+}
+
+/// Whether the path has both a prefix and a root.
+#[inline]
+pub fn is_absolute(path: &[u8]) -> bool {
+    let components = components(path);
+
+    components.prefix.is_some() && components.has_root()
+}
+
+/// Whether the path has only a root directory, like `\` or `/`.
+#[inline]
+fn is_root(path: &[u8]) -> bool {
+    let mut iter = components(path);
+
+    iter.next() == Some(Component::RootDir) && iter.next().is_none()
+}
+
+/// The components of the parent directory of the path, the same as `Path::parent().iter()`.
+fn parent_tokens(path: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut tokens: Vec<Component> = components(path).collect();
+
+    match tokens.pop() {
+        Some(Component::Normal(_)) | Some(Component::CurDir) | Some(Component::ParentDir) => {
+            Some(tokens.iter().map(Component::as_bytes).collect())
+        },
+        _ => None,
+    }
+}
+
+/// Append `path` to `buf` the same way as `PathBuf::push` does on Windows.
+pub fn push(buf: &mut Vec<u8>, path: &[u8]) {
+    let mut need_sep = buf.last().map(|&b| !is_separator(b)).unwrap_or(false);
+
+    let prefix = parse_prefix(buf);
+    let prefix_length = prefix.map(|p| p.len()).unwrap_or(0);
+
+    // in the special case of `C:`, do not add a separator
+    if prefix_length > 0
+        && prefix_length == buf.len()
+        && prefix.map(|p| p.is_drive()).unwrap_or(false)
+    {
+        need_sep = false;
+    }
+
+    if is_absolute(path) || parse_prefix(path).is_some() {
+        buf.clear();
+    } else if prefix.map(|p| p.is_verbatim()).unwrap_or(false) && !path.is_empty() {
+        // verbatim paths need `.` and `..` removed
+        let result = {
+            let mut tokens: Vec<Component> = components(buf).collect();
+
+            for component in components(path) {
+                match component {
+                    Component::RootDir => {
+                        tokens.truncate(1);
+                        tokens.push(component);
+                    },
+                    Component::CurDir => (),
+                    Component::ParentDir => {
+                        if let Some(Component::Normal(_)) = tokens.last() {
+                            tokens.pop();
+                        }
+                    },
+                    _ => tokens.push(component),
+                }
+            }
+
+            let mut result = Vec::with_capacity(buf.len() + path.len() + 1);
+
+            let mut need_sep = false;
+
+            for token in tokens {
+                if need_sep && token != Component::RootDir {
+                    result.push(SEPARATOR);
+                }
+
+                result.extend_from_slice(token.as_bytes());
+
+                need_sep = match token {
+                    Component::RootDir => false,
+                    Component::Prefix(prefix) => {
+                        !prefix.kind().is_drive() && prefix.kind().len() > 0
+                    },
+                    _ => true,
+                };
+            }
+
+            result
+        };
+
+        *buf = result;
+
+        return;
+    } else if has_root(path) {
+        buf.truncate(prefix_length);
+    } else if need_sep {
+        buf.push(SEPARATOR);
+    }
+
+    buf.extend_from_slice(path);
+}
+
+/// Join tokens with separators. `tokens` cannot be empty.
+fn join_tokens<'a>(
+    path: &'a [u8],
+    tokens: &[&[u8]],
+    has_prefix: bool,
+    first_is_root: bool,
+    has_change: bool,
+) -> Cow<'a, [u8]> {
+    let tokens_length = tokens.len();
+
+    debug_assert!(tokens_length > 0);
+
+    let mut size = tokens.iter().fold(tokens_length - 1, |acc, &x| acc + x.len());
+
+    if has_prefix {
+        if tokens_length > 1 {
+            size -= 1;
+
+            if first_is_root && tokens_length > 2 {
+                size -= 1;
+            }
+        }
+    } else if first_is_root && tokens_length > 1 {
+        size -= 1;
+    }
+
+    if has_change || size != path.len() {
+        let mut path_bytes = Vec::with_capacity(size);
+
+        let mut iter = tokens.iter();
+
+        let first = iter.next().unwrap();
+
+        if has_prefix {
+            // a prefix like `//server/share` is written with the main separator
+            path_bytes.extend(first.iter().map(|&b| if b == b'/' { SEPARATOR } else { b }));
+        } else {
+            path_bytes.extend_from_slice(first);
+        }
+
+        if tokens_length > 1 {
+            if has_prefix {
+                if let Some(token) = iter.next() {
+                    path_bytes.extend_from_slice(token);
+
+                    if tokens_length > 2 {
+                        if !first_is_root {
+                            path_bytes.push(SEPARATOR);
+                        }
+
+                        for token in iter.take(tokens_length - 3) {
+                            path_bytes.extend_from_slice(token);
+
+                            path_bytes.push(SEPARATOR);
+                        }
+
+                        path_bytes.extend_from_slice(tokens[tokens_length - 1]);
+                    }
+                }
+            } else {
+                if !first_is_root {
+                    path_bytes.push(SEPARATOR);
+                }
+
+                for token in iter.take(tokens_length - 2) {
+                    path_bytes.extend_from_slice(token);
+
+                    path_bytes.push(SEPARATOR);
+                }
+
+                path_bytes.extend_from_slice(tokens[tokens_length - 1]);
+            }
+        }
+
+        Cow::Owned(path_bytes)
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Select the current working directory which a path with `prefix` but without a root is resolved from.
+fn select_cwd<'a>(prefix: Prefix, cwd: &'a [u8], drive_cwds: &'a DriveCwds) -> &'a [u8] {
+    let drive = match prefix {
+        Prefix::Disk(drive) => drive,
+        _ => return cwd,
+    };
+
+    match get_path_prefix(cwd).map(|p| p.kind()) {
+        None => return cwd,
+        Some(Prefix::Disk(cwd_drive)) | Some(Prefix::VerbatimDisk(cwd_drive))
+            if cwd_drive == drive =>
+        {
+            return cwd
+        },
+        _ => (),
+    }
+
+    // the root directory is the current directory of a drive which has never been visited
+    drive_cwds.get(drive).unwrap_or(SEPARATOR_BYTES)
+}
+
+/// Push the tokens of the current working directory of `prefix` after the prefix, without the prefix of the current working directory. Returns whether the tokens have a root after the prefix.
+fn push_cwd_tokens_after_prefix<'a>(
+    tokens: &mut Vec<&'a [u8]>,
+    prefix: Prefix,
+    cwd: &'a [u8],
+    drive_cwds: &'a DriveCwds,
+    parent: bool,
+) -> bool {
+    let cwd = select_cwd(prefix, cwd, drive_cwds);
+
+    let skip = if get_path_prefix(cwd).is_some() { 1 } else { 0 };
+
+    if parent {
+        match parent_tokens(cwd) {
+            Some(cwd_parent) => tokens.extend(cwd_parent.into_iter().skip(skip)),
+            None => {
+                // don't care about `cwd` is "\\" or "\\\"
+                if (skip == 1 && is_absolute(cwd)) || (skip == 0 && is_root(cwd)) {
+                    tokens.push(SEPARATOR_BYTES);
+
+                    return true;
+                } else {
+                    return false;
+                }
+            },
+        }
+    } else {
+        tokens.extend(components(cwd).skip(skip).map(|c| c.as_bytes()));
+    }
+
+    tokens.len() > 1 && tokens[1] == SEPARATOR_BYTES
+}
+
+/// Whether the path must be kept as it is. Verbatim paths are passed to the system literally, so their dots and separators are not touched. A device namespace path without any component after the device, like `\\.\C:`, names the device itself.
+fn is_literal(path: &[u8]) -> bool {
+    match parse_prefix(path) {
+        Some(prefix) if prefix.is_verbatim() => true,
+        Some(Prefix::DeviceNS(_)) => components(path).nth(2).is_none(),
+        _ => false,
+    }
+}
+
+/// Push the tokens of `cwd`, or the tokens of the parent of `cwd` if `parent` is `true`. Returns `(has_prefix, first_is_root)`.
+fn push_cwd_tokens<'a>(tokens: &mut Vec<&'a [u8]>, cwd: &'a [u8], parent: bool) -> (bool, bool) {
+    let cwd_prefix = get_path_prefix(cwd);
+
+    if parent {
+        match parent_tokens(cwd) {
+            Some(cwd_parent) => tokens.extend(cwd_parent),
+            None => {
+                return match cwd_prefix {
+                    Some(prefix) => {
+                        tokens.push(prefix.as_bytes());
+
+                        if is_absolute(cwd) {
+                            tokens.push(SEPARATOR_BYTES);
+
+                            (true, true)
+                        } else {
+                            (true, false)
+                        }
+                    },
+                    None => {
+                        // don't care about `cwd` is "\\" or "\\\"
+                        if is_root(cwd) {
+                            tokens.push(SEPARATOR_BYTES);
+
+                            (false, true)
+                        } else {
+                            (false, false)
+                        }
+                    },
+                };
+            },
+        }
+    } else {
+        tokens.extend(components(cwd).map(|c| c.as_bytes()));
+    }
+
+    if cwd_prefix.is_some() {
+        (true, tokens.len() > 1 && tokens[1] == SEPARATOR_BYTES)
+    } else {
+        (false, !tokens.is_empty() && tokens[0] == SEPARATOR_BYTES)
+    }
+}
+
+/// Remove the `.` and `..` components after the first one.
+fn push_rest_tokens<'a, C: Count>(
+    tokens: &mut Vec<&'a [u8]>,
+    iter: Components<'a>,
+    has_prefix: bool,
+    first_is_root: bool,
+    counter: &mut C,
+    trace: &mut impl Trace,
+) -> Result<bool, C::Error> {
+    let mut has_change = false;
+
+    for component in iter {
+        counter.count(component == Component::ParentDir)?;
+
+        match component {
+            Component::CurDir => {
+                // may be unreachable
+                trace.record(|| Step::CurDirRemoved);
+
+                has_change = true;
+            },
+            Component::ParentDir => {
+                let tokens_length = tokens.len();
+
+                if tokens_length > 0
+                    && ((tokens_length != 1 || (!first_is_root && !has_prefix))
+                        && (tokens_length != 2 || !(first_is_root && has_prefix)))
+                {
+                    let name = tokens.remove(tokens_length - 1);
+
+                    trace.record(|| Step::ParentDirRemoved {
+                        name: name.to_vec()
+                    });
+                } else {
+                    trace.record(|| Step::ParentDirClamped);
+                }
+
+                has_change = true;
+            },
+            _ => {
+                trace.record(|| Step::Kept {
+                    name: component.as_bytes().to_vec()
+                });
+
+                tokens.push(component.as_bytes());
+            },
+        }
+    }
+
+    Ok(has_change)
+}
+
+/// Get an absolute path. `cwd` is the current working directory which relative paths are resolved from.
+#[inline]
+pub fn absolutize_from<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    absolutize_from_with(path, cwd, &Options::new())
+}
+
+/// Get an absolute path with options. `cwd` is the current working directory which relative paths are resolved from.
+///
+/// The limits in `options` are not checked. Use `try_absolutize_from_with` to check them.
+#[inline]
+pub fn absolutize_from_with<'a>(path: &'a [u8], cwd: &[u8], options: &Options) -> Cow<'a, [u8]> {
+    unwrap_infallible(absolutize_traced(path, cwd, options, &mut (), &mut ()))
+}
+
+/// Get an absolute path with options, and the steps taken to get it. See `absolutize_from_with` and `explain::Step`.
+pub fn explain_from_with<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+) -> (Cow<'a, [u8]>, Vec<Step>) {
+    let mut steps = Vec::new();
+
+    let path = unwrap_infallible(absolutize_traced(path, cwd, options, &mut (), &mut steps));
+
+    (path, steps)
+}
+
+fn absolutize_traced<'a, C: Count>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut C,
+    trace: &mut impl Trace,
+) -> Result<Cow<'a, [u8]>, C::Error> {
+    counter.check_length(path.len())?;
+
+    let path = match normalize_with(path, options) {
+        Cow::Borrowed(path) => absolutize_tokens(path, cwd, options, counter, trace)?,
+        Cow::Owned(path) => {
+            trace.record(|| Step::Win32Normalized {
+                path: path.clone()
+            });
+
+            Cow::Owned(absolutize_tokens(&path, cwd, options, counter, trace)?.into_owned())
+        },
+    };
+
+    Ok(apply_separator_style(path, options.separator_style, trace))
+}
+
+#[inline]
+fn normalize_with<'a>(path: &'a [u8], options: &Options) -> Cow<'a, [u8]> {
+    if options.win32_normalization {
+        normalize_win32(path)
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Rewrite the separators of a path in the given style. Verbatim paths are kept, because `/` is not a separator in them.
+fn apply_separator_style<'a>(
+    path: Cow<'a, [u8]>,
+    style: SeparatorStyle,
+    trace: &mut impl Trace,
+) -> Cow<'a, [u8]> {
+    let (from, to) = match style {
+        SeparatorStyle::Native => return path,
+        SeparatorStyle::ForwardSlash => (b'\\', b'/'),
+        SeparatorStyle::Backslash => (b'/', b'\\'),
+    };
+
+    if !path.contains(&from) || parse_prefix(&path).map(|p| p.is_verbatim()).unwrap_or(false) {
+        return path;
+    }
+
+    trace.record(|| Step::SeparatorsRewritten {
+        style,
+    });
+
+    let mut path = path.into_owned();
+
+    for b in path.iter_mut() {
+        if *b == from {
+            *b = to;
+        }
+    }
+
+    Cow::Owned(path)
+}
+
+/// Count the components of a path which is not walked, like a literal path.
+fn count_components<C: Count>(path: &[u8], counter: &mut C) -> Result<(), C::Error> {
+    for component in components(path) {
+        counter.count(component == Component::ParentDir)?;
+    }
+
+    Ok(())
+}
+
+fn absolutize_tokens<'a, C: Count>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut C,
+    trace: &mut impl Trace,
+) -> Result<Cow<'a, [u8]>, C::Error> {
+    if is_literal(path) {
+        count_components(path, counter)?;
+
+        trace.record(|| Step::Literal);
+
+        return Ok(Cow::Borrowed(path));
+    }
+
+    let mut iter = components(path);
+
+    let mut has_change = false;
+
+    if let Some(first_component) = iter.next() {
+        counter.count(first_component == Component::ParentDir)?;
+
+        let mut tokens = Vec::new();
+
+        let (has_prefix, first_is_root) = match first_component {
+            Component::Prefix(prefix) => {
+                tokens.push(prefix.as_bytes());
+
+                if prefix.as_bytes().contains(&b'/') {
+                    has_change = true;
+                }
+
+                if let Some(second_component) = iter.next() {
+                    counter.count(second_component == Component::ParentDir)?;
+
+                    let resolved_from_cwd = |parent| Step::Prefix {
+                        prefix:   prefix.as_bytes().to_vec(),
+                        handling: PrefixHandling::ResolvedFromCwd {
+                            cwd: select_cwd(prefix.kind(), cwd, &options.drive_cwds).to_vec(),
+                            parent,
+                        },
+                    };
+
+                    match second_component {
+                        Component::RootDir => {
+                            trace.record(|| Step::Prefix {
+                                prefix:   prefix.as_bytes().to_vec(),
+                                handling: PrefixHandling::Kept,
+                            });
+
+                            tokens.push(SEPARATOR_BYTES);
+
+                            (true, true)
+                        },
+                        Component::CurDir => {
+                            // may be unreachable
+
+                            has_change = true;
+
+                            trace.record(|| resolved_from_cwd(false));
+                            trace.record(|| Step::CurDirRemoved);
+
+                            (
+                                true,
+                                push_cwd_tokens_after_prefix(
+                                    &mut tokens,
+                                    prefix.kind(),
+                                    cwd,
+                                    &options.drive_cwds,
+                                    false,
+                                ),
+                            )
+                        },
+                        Component::ParentDir => {
+                            has_change = true;
+
+                            trace.record(|| resolved_from_cwd(true));
+
+                            (
+                                true,
+                                push_cwd_tokens_after_prefix(
+                                    &mut tokens,
+                                    prefix.kind(),
+                                    cwd,
+                                    &options.drive_cwds,
+                                    true,
+                                ),
+                            )
+                        },
+                        _ => {
+                            has_change = true;
+
+                            trace.record(|| resolved_from_cwd(false));
+
+                            let first_is_root = push_cwd_tokens_after_prefix(
+                                &mut tokens,
+                                prefix.kind(),
+                                cwd,
+                                &options.drive_cwds,
+                                false,
+                            );
+
+                            trace.record(|| Step::Kept {
+                                name: second_component.as_bytes().to_vec(),
+                            });
+
+                            tokens.push(second_component.as_bytes());
+
+                            (true, first_is_root)
+                        },
+                    }
+                } else {
+                    trace.record(|| Step::Prefix {
+                        prefix:   prefix.as_bytes().to_vec(),
+                        handling: PrefixHandling::RootAdded,
+                    });
+
+                    tokens.push(SEPARATOR_BYTES);
+
+                    has_change = true;
+
+                    (true, true)
+                }
+            },
+            Component::RootDir => {
+                has_change = true;
+
+                match get_path_prefix(cwd) {
+                    Some(prefix) => {
+                        trace.record(|| Step::Prefix {
+                            prefix:   prefix.as_bytes().to_vec(),
+                            handling: PrefixHandling::TakenFromCwd,
+                        });
+                        trace.record(|| Step::Root {
+                            root: SEPARATOR_BYTES.to_vec()
+                        });
+
+                        tokens.push(prefix.as_bytes());
+                        tokens.push(SEPARATOR_BYTES);
+
+                        (true, true)
+                    },
+                    None => {
+                        trace.record(|| Step::Root {
+                            root: SEPARATOR_BYTES.to_vec()
+                        });
+
+                        tokens.push(SEPARATOR_BYTES);
+
+                        (false, true)
+                    },
+                }
+            },
+            Component::CurDir => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: false
+                });
+                trace.record(|| Step::CurDirRemoved);
+
+                push_cwd_tokens(&mut tokens, cwd, false)
+            },
+            Component::ParentDir => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: true
+                });
+
+                push_cwd_tokens(&mut tokens, cwd, true)
+            },
+            Component::Normal(name) => {
+                has_change = true;
+
+                trace.record(|| Step::ResolvedFromCwd {
+                    cwd: cwd.to_vec(), parent: false
+                });
+
+                let out = push_cwd_tokens(&mut tokens, cwd, false);
+
+                trace.record(|| Step::Kept {
+                    name: name.to_vec()
+                });
+
+                tokens.push(name);
+
+                out
+            },
+        };
+
+        if push_rest_tokens(&mut tokens, iter, has_prefix, first_is_root, counter, trace)? {
+            has_change = true;
+        }
+
+        if tokens.is_empty() {
+            // `cwd` is empty and every component has been removed
+            return Ok(Cow::Owned(Vec::new()));
+        }
+
+        Ok(join_tokens(path, &tokens, has_prefix, first_is_root, has_change))
+    } else {
+        trace.record(|| Step::Empty);
+
+        Ok(Cow::Owned(cwd.to_vec()))
+    }
+}
+
+/// Get an absolute path with options, checking the limits in `options`. See `absolutize_from` and `Limits`.
+pub fn try_absolutize_from_with<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let path = absolutize_traced(path, cwd, options, &mut options.limits.counter(), &mut ())?;
+
+    options.limits.check_output(path.len())?;
+
+    Ok(path)
+}
+
+/// Remove the dots in a path. Only a leading single dot or a leading pair of dots is resolved from `cwd`, so a relative path stays relative otherwise.
+#[inline]
+pub fn parse_dot_from<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    parse_dot_from_with(path, cwd, &Options::new())
+}
+
+/// Remove the dots in a path with options. See `parse_dot_from`.
+#[inline]
+pub fn parse_dot_from_with<'a>(path: &'a [u8], cwd: &[u8], options: &Options) -> Cow<'a, [u8]> {
+    unwrap_infallible(parse_dot_counted(path, cwd, options, &mut ()))
+}
+
+fn parse_dot_counted<'a, C: Count>(
+    path: &'a [u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut C,
+) -> Result<Cow<'a, [u8]>, C::Error> {
+    if is_literal(path) {
+        count_components(path, counter)?;
+
+        return Ok(Cow::Borrowed(path));
+    }
+
+    let mut iter = components(path);
+
+    let mut has_dots = false;
+
+    if let Some(first_component) = iter.next() {
+        counter.count(first_component == Component::ParentDir)?;
+
+        let mut tokens = Vec::new();
+
+        let (has_prefix, first_is_root) = match first_component {
+            Component::Prefix(prefix) => {
+                tokens.push(prefix.as_bytes());
+
+                if prefix.as_bytes().contains(&b'/') {
+                    has_dots = true;
+                }
+
+                if let Some(second_component) = iter.next() {
+                    counter.count(second_component == Component::ParentDir)?;
+
+                    match second_component {
+                        Component::RootDir => {
+                            tokens.push(SEPARATOR_BYTES);
+
+                            (true, true)
+                        },
+                        Component::CurDir => {
+                            // may be unreachable
+
+                            has_dots = true;
+
+                            (
+                                true,
+                                push_cwd_tokens_after_prefix(
+                                    &mut tokens,
+                                    prefix.kind(),
+                                    cwd,
+                                    &options.drive_cwds,
+                                    false,
+                                ),
+                            )
+                        },
+                        Component::ParentDir => {
+                            has_dots = true;
+
+                            (
+                                true,
+                                push_cwd_tokens_after_prefix(
+                                    &mut tokens,
+                                    prefix.kind(),
+                                    cwd,
+                                    &options.drive_cwds,
+                                    true,
+                                ),
+                            )
+                        },
+                        _ => {
+                            tokens.push(second_component.as_bytes());
+
+                            (true, false)
+                        },
+                    }
+                } else {
+                    (true, false)
+                }
+            },
+            Component::RootDir => {
+                tokens.push(SEPARATOR_BYTES);
+
+                (false, true)
+            },
+            Component::CurDir => {
+                has_dots = true;
+
+                push_cwd_tokens(&mut tokens, cwd, false)
+            },
+            Component::ParentDir => {
+                has_dots = true;
+
+                push_cwd_tokens(&mut tokens, cwd, true)
+            },
+            Component::Normal(name) => {
+                tokens.push(name);
+
+                (false, false)
+            },
+        };
+
+        if push_rest_tokens(&mut tokens, iter, has_prefix, first_is_root, counter, &mut ())? {
+            has_dots = true;
+        }
+
+        if tokens.is_empty() {
+            return Ok(Cow::Owned(Vec::new()));
+        }
+
+        if has_prefix && first_is_root && tokens.len() == 2 && tokens[0] == path {
+            // `\\server\share` -> `\\server\share\` should still be `\\server\share`
+            return Ok(Cow::Borrowed(path));
+        }
+
+        Ok(join_tokens(path, &tokens, has_prefix, first_is_root, has_dots))
+    } else {
+        Ok(Cow::Borrowed(path))
+    }
+}
+
+/// Find the anomalies in a path without rewriting it, i.e., redundant separators, `.`, `..` which climbs out of the base directory, trailing separators, separators which differ from the first one, and a prefix or a root directory if `rules` expects a relative path. The spans are byte ranges of `path`.
+///
+/// Verbatim paths are passed to the system literally, so only `AnomalyKind::NotRelative` is reported for them.
+pub fn analyze(path: &[u8], rules: &AnalysisRules) -> PathReport {
+    let mut report = PathReport::default();
+
+    let prefix = get_path_prefix(path);
+
+    let prefix_end = prefix.map(|prefix| prefix.as_bytes().len()).unwrap_or(0);
+
+    if rules.expect_relative && (prefix.is_some() || has_root(path)) {
+        let root_end =
+            prefix_end + path[prefix_end..].iter().take_while(|&&b| is_separator(b)).count();
+
+        report.push(AnomalyKind::NotRelative, 0..root_end);
+    }
+
+    if prefix.map(|prefix| prefix.kind().is_verbatim()).unwrap_or(false) {
+        return report;
+    }
+
+    if let Some(&first) = path.iter().find(|&&b| is_separator(b)) {
+        for (i, &b) in path.iter().enumerate() {
+            if is_separator(b) && b != first {
+                report.push(AnomalyKind::MixedSeparators, i..i + 1);
+            }
+        }
+    }
+
+    let mut iter = components(path);
+
+    let parts = core::iter::from_fn(|| loop {
+        let component = iter.next()?;
+
+        let rest_length = iter.path.len();
+
+        return Some(match component {
+            Component::Prefix(_) => continue,
+            Component::RootDir if iter.has_physical_root => {
+                (Part::RootDir, prefix_end..prefix_end + 1)
+            },
+            // the root directory implied by a prefix like `\\server\share` has no byte
+            Component::RootDir => continue,
+            Component::CurDir => {
+                (Part::CurDir, analyze::name_span(path, rest_length, 1, is_separator))
+            },
+            Component::ParentDir => {
+                (Part::ParentDir, analyze::name_span(path, rest_length, 2, is_separator))
+            },
+            Component::Normal(name) => {
+                (Part::Normal, analyze::name_span(path, rest_length, name.len(), is_separator))
+            },
+        });
+    });
+
+    analyze::report_parts(&mut report, path, prefix_end, parts, is_separator);
+
+    report.finish()
+}
+
+/// Find the names in a path which `policy` rejects. A name can have several violations.
+pub fn policy_violations<'a>(path: &'a [u8], policy: &PathPolicy) -> Vec<PolicyViolation<'a>> {
+    let mut violations = Vec::new();
+
+    let mut count = 0;
+
+    for (index, component) in components(path).enumerate() {
+        match component {
+            Component::Normal(name) => {
+                count += 1;
+
+                policy.check_name(&mut violations, index, count, name, false);
+            },
+            Component::ParentDir => {
+                count += 1;
+
+                policy.check_name(&mut violations, index, count, b"..", true);
+            },
+            _ => (),
+        }
+    }
+
+    violations
+}
+
+/// Get an absolute path **only under a specific directory**. `cwd` is the current working directory which `virtual_root` and a leading dot of `path` are resolved from.
+#[inline]
+pub fn absolutize_virtually<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    absolutize_virtually_with(path, virtual_root, cwd, &Options::new())
+}
+
+/// Get an absolute path **only under a specific directory** with options. See `absolutize_virtually`.
+pub fn absolutize_virtually_with<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let mut counter = options.limits.counter();
+
+    counter.check_length(path.len())?;
+
+    let input = path;
+
+    let path = match normalize_with(path, options) {
+        Cow::Borrowed(path) => {
+            absolutize_virtually_tokens(input, path, virtual_root, cwd, options, &mut counter)?
+        },
+        Cow::Owned(path) => Cow::Owned(
+            absolutize_virtually_tokens(input, &path, virtual_root, cwd, options, &mut counter)?
+                .into_owned(),
+        ),
+    };
+
+    options.limits.check_output(path.len())?;
+
+    Ok(apply_separator_style(path, options.separator_style, &mut ()))
+}
+
+/// `input` is the path before the Win32 normalization, which the policy checks.
+fn absolutize_virtually_tokens<'a>(
+    input: &[u8],
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    options: &Options,
+    counter: &mut LimitCounter,
+) -> Result<Cow<'a, [u8]>, Error> {
+    if options.path_policy != PathPolicy::new() {
+        policy::first_violation(&policy_violations(input, &options.path_policy))?;
+    }
+
+    let path = parse_dot_counted(path, cwd, options, counter)?;
+
+    let virtual_root = absolutize_from_with(virtual_root, cwd, options);
+
+    if is_absolute(&path) {
+        if !starts_with_ignore_case(&path, &virtual_root) {
+            return Err(Error::OutsideVirtualRoot);
+        }
+
+        return Ok(path);
+    }
+
+    let joined = if let Some(prefix) = get_path_prefix(&path) {
+        let same_prefix = match get_path_prefix(&virtual_root) {
+            Some(virtual_root_prefix) => {
+                ignore_case::prefix_eq_ignore_case(prefix.kind(), virtual_root_prefix.kind())
+            },
+            None => false,
+        };
+
+        if !same_prefix {
+            return Err(Error::OutsideVirtualRoot);
+        }
+
+        let mut joined = virtual_root.to_vec();
+
+        let rest = &path[prefix.as_bytes().len()..];
+
+        // `push` appends a separator even if the path is empty
+        if !rest.is_empty() {
+            push(&mut joined, rest);
+        }
+
+        joined
+    } else {
+        let mut joined = virtual_root.to_vec();
+
+        if !path.is_empty() {
+            push(&mut joined, &path);
+        }
+
+        joined
+    };
+
+    // a path with a root but without a prefix, like `\foo`, replaces everything after the prefix of the virtual root
+    if !starts_with_ignore_case(&joined, &virtual_root) {
+        return Err(Error::OutsideVirtualRoot);
+    }
+
+    Ok(Cow::Owned(joined))
+}
+
+/// Get the path relative to `virtual_root` with a leading `\`, i.e., `C:\srv\root\a\b` under `C:\srv\root` becomes `\a\b`. This is the inverse of `absolutize_virtually`, and it compares the paths the same way, ignoring case. Both paths are absolutized from `cwd` first.
+#[inline]
+pub fn virtualize<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    virtualize_with(path, virtual_root, cwd, &Options::new())
+}
+
+/// Get the path relative to `virtual_root` with a leading `\` with options. See `virtualize`.
+pub fn virtualize_with<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    options: &Options,
+) -> Result<Cow<'a, [u8]>, Error> {
+    let virtual_root = absolutize_from_with(virtual_root, cwd, options);
+
+    let path = absolutize_from_with(path, cwd, options);
+
+    if !starts_with_ignore_case(&path, &virtual_root) {
+        return Err(Error::OutsideVirtualRoot);
+    }
+
+    let mut rest = components(&path);
+
+    for _ in components(&virtual_root) {
+        rest.next();
+    }
+
+    let mut virtualized = Vec::with_capacity(path.len());
+
+    for component in rest {
+        virtualized.push(SEPARATOR);
+        virtualized.extend_from_slice(component.as_bytes());
+    }
+
+    if virtualized.is_empty() {
+        virtualized.push(SEPARATOR);
+    }
+
+    Ok(apply_separator_style(Cow::Owned(virtualized), options.separator_style, &mut ()))
+}
@@ -0,0 +1,110 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use super::{
+    absolutize_from_with, parse_prefix, win32::is_reserved_name, wtf8, Options, Prefix, SEPARATOR,
+};
+
+/// The maximum length of a path, in UTF-16 code units and including the terminating null character, which Win32 APIs accept without the `\\?\` prefix.
+pub const MAX_PATH: usize = 260;
+
+/// The length of WTF-8 bytes in UTF-16 code units.
+fn wide_len(bytes: &[u8]) -> usize {
+    let mut length = 0;
+
+    let mut i = 0;
+
+    while let Some(code_point) = wtf8::next_code_point(bytes, &mut i) {
+        length += if code_point >= 0x10000 { 2 } else { 1 };
+    }
+
+    length
+}
+
+/// Get an absolute path in the verbatim form, i.e., `C:\a` becomes `\\?\C:\a`, `\\server\share\a` becomes `\\?\UNC\server\share\a` and `\\.\device` becomes `\\?\device`. `cwd` is the current working directory which relative paths are resolved from.
+///
+/// Verbatim paths are returned as they are. If the absolute path has no prefix, which happens only when `cwd` has no prefix, it is returned in the non-verbatim form.
+#[inline]
+pub fn to_verbatim_from<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    to_verbatim_from_with(path, cwd, &Options::new())
+}
+
+/// Get an absolute path in the verbatim form with options. See `to_verbatim_from`.
+pub fn to_verbatim_from_with<'a>(path: &'a [u8], cwd: &[u8], options: &Options) -> Cow<'a, [u8]> {
+    let path = absolutize_from_with(path, cwd, options);
+
+    let (head, rest): (&[u8], &[u8]) = match parse_prefix(&path) {
+        Some(Prefix::Disk(_)) => (br"\\?\", &path),
+        Some(Prefix::UNC(..)) => (br"\\?\UNC\", &path[2..]),
+        Some(Prefix::DeviceNS(_)) => (br"\\?\", &path[4..]),
+        _ => return path,
+    };
+
+    let mut verbatim = Vec::with_capacity(head.len() + rest.len());
+
+    verbatim.extend_from_slice(head);
+
+    // `/` is not a separator in verbatim paths
+    verbatim.extend(rest.iter().map(|&b| if b == b'/' { SEPARATOR } else { b }));
+
+    Cow::Owned(verbatim)
+}
+
+/// Remove the `\\?\` prefix of a path if the path means the same file without it, i.e., `\\?\C:\a` becomes `C:\a` and `\\?\UNC\server\share\a` becomes `\\server\share\a`.
+///
+/// The prefix is kept if the path has a component that Win32 would change or interpret, like `.`, `..`, an empty component, a name with trailing dots or spaces, a reserved name like `NUL`, or a `/`, or if the simplified path is not shorter than `MAX_PATH`. Other paths are returned as they are.
+pub fn simplify_verbatim(path: &[u8]) -> Cow<'_, [u8]> {
+    let (head, rest): (&[u8], &[u8]) = match parse_prefix(path) {
+        Some(Prefix::VerbatimDisk(_)) if path.len() > 6 => (b"", &path[4..]),
+        Some(Prefix::VerbatimUNC(_, share)) if !share.is_empty() => (br"\\", &path[8..]),
+        _ => return Cow::Borrowed(path),
+    };
+
+    if path.contains(&b'/') {
+        return Cow::Borrowed(path);
+    }
+
+    // skip the drive or `server\share`
+    let body = match head.len() {
+        0 => &rest[2..],
+        _ => {
+            let server_length = rest.iter().position(|&b| b == SEPARATOR).unwrap();
+
+            let rest = &rest[server_length + 1..];
+
+            match rest.iter().position(|&b| b == SEPARATOR) {
+                Some(share_length) => &rest[share_length..],
+                None => &rest[rest.len()..],
+            }
+        },
+    };
+
+    if !body.is_empty() {
+        let mut names = body[1..].split(|&b| b == SEPARATOR);
+
+        // a trailing separator is fine
+        if body.ends_with(&[SEPARATOR]) {
+            names.next_back();
+        }
+
+        for name in names {
+            if name.is_empty()
+                || name.ends_with(b".")
+                || name.ends_with(b" ")
+                || is_reserved_name(name)
+            {
+                return Cow::Borrowed(path);
+            }
+        }
+    }
+
+    if wide_len(head) + wide_len(rest) >= MAX_PATH {
+        return Cow::Borrowed(path);
+    }
+
+    let mut simplified = Vec::with_capacity(head.len() + rest.len());
+
+    simplified.extend_from_slice(head);
+    simplified.extend_from_slice(rest);
+
+    Cow::Owned(simplified)
+}
@@ -0,0 +1,180 @@
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use super::{components, is_separator, parse_prefix, Component, Error};
+
+/// A kind of names which Win32 does not open as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Win32IssueKind {
+    /// The name ends with a dot, which Win32 strips, like `file.`.
+    TrailingDot,
+    /// The name ends with a space, which Win32 strips, like `file `.
+    TrailingSpace,
+    /// The name is a reserved DOS device name, which Win32 opens as the device, like `CON`, `nul.txt` or `COM1 .log`.
+    ReservedName,
+    /// The name contains a colon, which Win32 parses as an alternate data stream, like `file:stream`.
+    AlternateDataStream,
+}
+
+impl Display for Win32IssueKind {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Win32IssueKind::TrailingDot => f.write_str("it ends with a dot"),
+            Win32IssueKind::TrailingSpace => f.write_str("it ends with a space"),
+            Win32IssueKind::ReservedName => f.write_str("it is a reserved device name"),
+            Win32IssueKind::AlternateDataStream => {
+                f.write_str("it contains a colon, which means an alternate data stream")
+            },
+        }
+    }
+}
+
+/// A name in a path which Win32 does not open as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win32Issue<'a> {
+    /// What is wrong with the name.
+    pub kind:  Win32IssueKind,
+    /// The index of the component, counted the same way as `components` does, including the prefix and the root directory.
+    pub index: usize,
+    /// The name.
+    pub name:  &'a [u8],
+}
+
+/// Whether a file name is a reserved DOS device name, like `CON`, `nul.txt` or `COM1 .log`. Such names are only valid in verbatim paths.
+pub(crate) fn is_reserved_name(name: &[u8]) -> bool {
+    // the extension and the trailing spaces of the base name are ignored
+    let base = match name.iter().position(|&b| b == b'.' || b == b':') {
+        Some(index) => &name[..index],
+        None => name,
+    };
+
+    let base = match base.iter().rposition(|&b| b != b' ') {
+        Some(index) => &base[..=index],
+        None => return false,
+    };
+
+    let eq = |bytes: &[u8], expected: &[u8]| bytes.eq_ignore_ascii_case(expected);
+
+    match base.len() {
+        3 => ["CON", "PRN", "AUX", "NUL"].iter().any(|&n| eq(base, n.as_bytes())),
+        4 => (eq(&base[..3], b"COM") || eq(&base[..3], b"LPT")) && base[3].is_ascii_digit(),
+        // COM¹, COM², COM³, LPT¹, LPT², LPT³
+        5 => {
+            (eq(&base[..3], b"COM") || eq(&base[..3], b"LPT"))
+                && matches!(&base[3..], [0xC2, 0xB9] | [0xC2, 0xB2] | [0xC2, 0xB3])
+        },
+        6 => eq(base, b"CONIN$"),
+        7 => eq(base, b"CONOUT$"),
+        _ => false,
+    }
+}
+
+/// Find the names in a path which Win32 does not open as they are, i.e., names with trailing dots or spaces, reserved device names and alternate data streams. Every name has at most one issue, the first one in that order.
+///
+/// Verbatim paths are checked, too, since Win32 cannot open such names without the `\\?\` prefix.
+pub fn win32_issues(path: &[u8]) -> Vec<Win32Issue<'_>> {
+    let mut issues = Vec::new();
+
+    for (index, component) in components(path).enumerate() {
+        let name = match component {
+            Component::Normal(name) => name,
+            _ => continue,
+        };
+
+        let kind = if name.ends_with(b".") {
+            Win32IssueKind::TrailingDot
+        } else if name.ends_with(b" ") {
+            Win32IssueKind::TrailingSpace
+        } else if is_reserved_name(name) {
+            Win32IssueKind::ReservedName
+        } else if name.contains(&b':') {
+            Win32IssueKind::AlternateDataStream
+        } else {
+            continue;
+        };
+
+        issues.push(Win32Issue {
+            kind,
+            index,
+            name,
+        });
+    }
+
+    issues
+}
+
+/// Reject a path which has a name that Win32 does not open as it is. See `win32_issues`.
+#[inline]
+pub fn validate_win32(path: &[u8]) -> Result<(), Error> {
+    match win32_issues(path).first() {
+        Some(issue) => Err(Error::Win32Name {
+            kind: issue.kind, index: issue.index
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Normalize the names in a path the same way as Win32 does before opening a file. A name which ends with a single dot loses the dot, and the last name loses all of its trailing dots and spaces unless the path ends with a separator. Names which consist only of dots are kept.
+///
+/// Verbatim paths are returned as they are. Reserved names and alternate data streams cannot be normalized, so use `validate_win32` to reject them.
+pub fn normalize_win32(path: &[u8]) -> Cow<'_, [u8]> {
+    let prefix = parse_prefix(path);
+
+    if prefix.map(|p| p.is_verbatim()).unwrap_or(false) {
+        return Cow::Borrowed(path);
+    }
+
+    let start = prefix.map(|p| p.len()).unwrap_or(0);
+
+    let mut normalized: Option<Vec<u8>> = None;
+
+    let mut segment_start = start;
+
+    while segment_start <= path.len() {
+        let segment_end = path[segment_start..]
+            .iter()
+            .position(|&b| is_separator(b))
+            .map(|i| segment_start + i)
+            .unwrap_or(path.len());
+
+        let segment = &path[segment_start..segment_end];
+
+        let mut trimmed = segment;
+
+        if !trimmed.iter().all(|&b| b == b'.') {
+            if trimmed.ends_with(b".") {
+                trimmed = &trimmed[..trimmed.len() - 1];
+            }
+
+            if segment_end == path.len() {
+                let length = trimmed
+                    .iter()
+                    .rposition(|&b| b != b'.' && b != b' ')
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+
+                trimmed = &trimmed[..length];
+            }
+        }
+
+        if trimmed.len() != segment.len() && normalized.is_none() {
+            normalized = Some(path[..segment_start].to_vec());
+        }
+
+        if let Some(normalized) = normalized.as_mut() {
+            normalized.extend_from_slice(trimmed);
+
+            if segment_end < path.len() {
+                normalized.push(path[segment_end]);
+            }
+        }
+
+        segment_start = segment_end + 1;
+    }
+
+    match normalized {
+        Some(normalized) => Cow::Owned(normalized),
+        None => Cow::Borrowed(path),
+    }
+}
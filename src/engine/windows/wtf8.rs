@@ -0,0 +1,132 @@
+//! Conversions between WTF-16, the native encoding of Windows paths, and WTF-8, the encoding used by the Windows engine.
+//!
+//! WTF-8 is UTF-8 extended to allow unpaired surrogates, so every sequence of `u16` can be represented losslessly. Valid UTF-8 is valid WTF-8 as it is.
+
+use alloc::vec::Vec;
+
+/// Encode WTF-16 code units into WTF-8 bytes.
+pub fn from_wide(units: &[u16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(units.len());
+
+    let mut i = 0;
+
+    while i < units.len() {
+        let unit = units[i] as u32;
+
+        i += 1;
+
+        let code_point = if (0xD800..0xDC00).contains(&unit)
+            && i < units.len()
+            && (0xDC00..0xE000).contains(&(units[i] as u32))
+        {
+            let low = units[i] as u32;
+
+            i += 1;
+
+            0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            unit
+        };
+
+        push_code_point(&mut bytes, code_point);
+    }
+
+    bytes
+}
+
+/// Decode WTF-8 bytes into WTF-16 code units. Bytes which are not WTF-8 are decoded as U+FFFD.
+pub fn to_wide(bytes: &[u8]) -> Vec<u16> {
+    let mut units = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+
+    while let Some(code_point) = next_code_point(bytes, &mut i) {
+        if code_point >= 0x10000 {
+            let code_point = code_point - 0x10000;
+
+            units.push(0xD800 | (code_point >> 10) as u16);
+            units.push(0xDC00 | (code_point & 0x3FF) as u16);
+        } else {
+            units.push(code_point as u16);
+        }
+    }
+
+    units
+}
+
+/// Append a code point, which may be a surrogate, to WTF-8 bytes.
+pub(crate) fn push_code_point(bytes: &mut Vec<u8>, code_point: u32) {
+    match code_point {
+        0..=0x7F => bytes.push(code_point as u8),
+        0x80..=0x7FF => {
+            bytes.push(0xC0 | (code_point >> 6) as u8);
+            bytes.push(0x80 | (code_point & 0x3F) as u8);
+        },
+        0x800..=0xFFFF => {
+            bytes.push(0xE0 | (code_point >> 12) as u8);
+            bytes.push(0x80 | ((code_point >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (code_point & 0x3F) as u8);
+        },
+        _ => {
+            bytes.push(0xF0 | (code_point >> 18) as u8);
+            bytes.push(0x80 | ((code_point >> 12) & 0x3F) as u8);
+            bytes.push(0x80 | ((code_point >> 6) & 0x3F) as u8);
+            bytes.push(0x80 | (code_point & 0x3F) as u8);
+        },
+    }
+}
+
+/// Decode the code point at `*i` and advance `*i`. Invalid sequences are decoded byte by byte as U+FFFD.
+pub(crate) fn next_code_point(bytes: &[u8], i: &mut usize) -> Option<u32> {
+    let first = *bytes.get(*i)?;
+
+    let (length, init) = match first {
+        0x00..=0x7F => {
+            *i += 1;
+
+            return Some(first as u32);
+        },
+        0xC2..=0xDF => (2, (first & 0x1F) as u32),
+        0xE0..=0xEF => (3, (first & 0x0F) as u32),
+        0xF0..=0xF4 => (4, (first & 0x07) as u32),
+        _ => {
+            *i += 1;
+
+            return Some(0xFFFD);
+        },
+    };
+
+    if *i + length > bytes.len() {
+        *i += 1;
+
+        return Some(0xFFFD);
+    }
+
+    let mut code_point = init;
+
+    for &b in &bytes[*i + 1..*i + length] {
+        if b & 0xC0 != 0x80 {
+            *i += 1;
+
+            return Some(0xFFFD);
+        }
+
+        code_point = (code_point << 6) | (b & 0x3F) as u32;
+    }
+
+    let overlong = match length {
+        3 => code_point < 0x800,
+        4 => !(0x10000..=0x10FFFF).contains(&code_point),
+        _ => false,
+    };
+
+    if overlong {
+        *i += 1;
+
+        return Some(0xFFFD);
+    }
+
+    *i += length;
+
+    Some(code_point)
+}
@@ -0,0 +1,159 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::engine::explain::Step;
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+/// Where the current working directory of an `Explanation` comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwdSource {
+    /// It is passed to `absolutize_from_explained`.
+    Given,
+    /// It is got from `std::env::current_dir`.
+    CurrentDir,
+    /// It is cached by the crate feature `feature`, like `once_cell_cache`, so it can be stale if the current working directory has been changed.
+    Cache { feature: &'static str },
+    /// On Windows, it is the current directory of the drive of a drive-relative path, like `D:a`, kept in the `=D:` environment variable.
+    DriveVariable { drive: char },
+    /// On Windows, it is the root directory of the drive of a drive-relative path, like `D:a`, whose drive has no current directory.
+    DriveRoot { drive: char },
+}
+
+impl CwdSource {
+    /// The source which `absolutize` gets the current working directory from, according to the enabled crate features.
+    #[inline]
+    pub const fn current() -> Self {
+        if cfg!(feature = "once_cell_cache") {
+            CwdSource::Cache {
+                feature: "once_cell_cache"
+            }
+        } else if cfg!(feature = "lazy_static_cache") {
+            CwdSource::Cache {
+                feature: "lazy_static_cache"
+            }
+        } else if cfg!(feature = "unsafe_cache") {
+            CwdSource::Cache {
+                feature: "unsafe_cache"
+            }
+        } else {
+            CwdSource::CurrentDir
+        }
+    }
+}
+
+impl Display for CwdSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CwdSource::Given => f.write_str("given"),
+            CwdSource::CurrentDir => f.write_str("from `std::env::current_dir`"),
+            CwdSource::Cache {
+                feature,
+            } => write!(f, "from the cache of the `{}` feature", feature),
+            CwdSource::DriveVariable {
+                drive,
+            } => write!(f, "from the `={}:` environment variable", drive),
+            CwdSource::DriveRoot {
+                drive,
+            } => write!(f, "the root directory of the drive `{}:`", drive),
+        }
+    }
+}
+
+/// An absolute path with the steps taken to get it. It can be displayed in multiple lines for logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    /// The input path.
+    pub input:      &'a Path,
+    /// The absolute path, the same as `absolutize` or `absolutize_from` returns.
+    pub path:       Cow<'a, Path>,
+    /// The current working directory which the path is resolved from.
+    pub cwd:        PathBuf,
+    /// Where `cwd` comes from.
+    pub cwd_source: CwdSource,
+    /// The steps taken by the engine.
+    pub steps:      Vec<Step>,
+}
+
+impl<'a> Display for Explanation<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "`{}` -> `{}`", self.input.display(), self.path.display())?;
+        write!(f, "current working directory `{}`, {}", self.cwd.display(), self.cwd_source)?;
+
+        for (i, step) in self.steps.iter().enumerate() {
+            write!(f, "\n{}. {}", i + 1, step)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The result of the `explain` function of the hosts: the absolute path, the steps, and the current working directory which the path is actually resolved from with its source, if it is not the given one.
+pub(crate) type Explained<'a> = (Cow<'a, [u8]>, Vec<Step>, Option<(Vec<u8>, CwdSource)>);
+
+/// Let `Path` and `PathBuf` explain how they are absolutized, for finding out why a path is resolved to something unexpected.
+pub trait AbsolutizeExplained {
+    /// Get an absolute path the same as `absolutize` does, and the steps taken to get it.
+    fn absolutize_explained(&self) -> io::Result<Explanation<'_>>;
+
+    /// Get an absolute path the same as `absolutize_from` does, and the steps taken to get it.
+    fn absolutize_from_explained(&self, cwd: impl AsRef<Path>) -> io::Result<Explanation<'_>>;
+}
+
+impl AbsolutizeExplained for Path {
+    #[inline]
+    fn absolutize_explained(&self) -> io::Result<Explanation<'_>> {
+        let cwd = get_cwd!();
+
+        Ok(explain(self, Path::new(cwd.as_os_str()), CwdSource::current(), true))
+    }
+
+    #[inline]
+    fn absolutize_from_explained(&self, cwd: impl AsRef<Path>) -> io::Result<Explanation<'_>> {
+        Ok(explain(self, cwd.as_ref(), CwdSource::Given, false))
+    }
+}
+
+fn explain<'a>(
+    input: &'a Path,
+    cwd: &Path,
+    cwd_source: CwdSource,
+    use_drive_cwds: bool,
+) -> Explanation<'a> {
+    let path = host::to_bytes(input.as_os_str());
+    let cwd_bytes = host::to_bytes(cwd.as_os_str());
+
+    let (result, steps, resolved_from) = host::explain(&path, &cwd_bytes, use_drive_cwds);
+
+    let (cwd, cwd_source) = match resolved_from {
+        Some((cwd, cwd_source)) => (host::from_bytes(cwd), cwd_source),
+        None => (cwd.to_path_buf(), cwd_source),
+    };
+
+    Explanation {
+        input,
+        path: host::from_engine(input, result),
+        cwd,
+        cwd_source,
+        steps,
+    }
+}
+
+impl AbsolutizeExplained for PathBuf {
+    #[inline]
+    fn absolutize_explained(&self) -> io::Result<Explanation<'_>> {
+        self.as_path().absolutize_explained()
+    }
+
+    #[inline]
+    fn absolutize_from_explained(&self, cwd: impl AsRef<Path>) -> io::Result<Explanation<'_>> {
+        self.as_path().absolutize_from_explained(cwd)
+    }
+}
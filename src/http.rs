@@ -0,0 +1,43 @@
+use std::{
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    engine::http::{decode_request_path, RequestPolicy},
+    Absolutize,
+};
+
+/// Map the path of an HTTP request, without the query and the fragment, to a path under the document root `doc_root`, which is absolutized.
+///
+/// The request path is percent-decoded, validated according to `policy`, and then jailed under `doc_root` the same way as `absolutize_virtually` does. See `engine::http::decode_request_path`.
+///
+/// Returns an error of `ErrorKind::InvalidInput` if the request path is rejected, leaves `doc_root` or is not valid UTF-8 after decoding.
+pub fn map_request_path(
+    url_path: &str,
+    doc_root: impl AsRef<Path>,
+    policy: &RequestPolicy,
+) -> io::Result<PathBuf> {
+    let decoded =
+        String::from_utf8(decode_request_path(url_path.as_bytes(), policy)?).map_err(|_| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "The request path is not valid UTF-8 after decoding.",
+            )
+        })?;
+
+    let doc_root = doc_root.as_ref();
+
+    if decoded.is_empty() {
+        return Ok(doc_root.absolutize()?.into_owned());
+    }
+
+    let mut path = Path::new(&decoded).absolutize_virtually(doc_root)?.into_owned();
+
+    // `TrailingSlash::Keep`
+    if decoded.ends_with('/') {
+        path.push("");
+    }
+
+    Ok(path)
+}
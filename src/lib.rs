@@ -379,6 +379,394 @@ unsafe {
 println!("{}", p.absolutize().unwrap().to_str().unwrap());
 ```
 
+### MemoAbsolutizer
+
+The caches above only save getting the CWD, not the normalization. `MemoAbsolutizer` also remembers the results, in a bounded least recently used cache, for programs which absolutize the same relative paths again and again, like build tools.
+
+```rust
+use std::{path::Path, sync::Arc};
+
+use path_absolutize::MemoAbsolutizer;
+
+# if cfg!(unix) {
+let memo = MemoAbsolutizer::with_cwd(4096, "/cwd");
+
+let a = memo.absolutize("./src/../lib").unwrap();
+
+assert_eq!(Path::new("/cwd/lib"), a.as_ref());
+assert!(Arc::ptr_eq(&a, &memo.absolutize("./src/../lib").unwrap()));
+# }
+```
+
+## Serde Support
+
+Enabling the `serde` feature makes `AbsolutePathBuf` serializable and deserializable, and adds helpers in the `serde` module that absolutize paths while they are deserialized.
+
+```toml
+[dependencies.path-absolutize]
+version = "*"
+features = ["serde"]
+```
+
+In a struct deriving `Deserialize`, put `#[serde(deserialize_with = "path_absolutize::serde::deserialize")]` on a `PathBuf` field, or use `AbsolutePathBuf` as the type of the field. Both can also be called on any deserializer directly.
+
+```rust
+# #[cfg(feature = "serde")]
+# {
+use path_absolutize::AbsolutePathBuf;
+use serde::{
+    de::value::{Error, StrDeserializer},
+    Deserialize,
+};
+
+let input = path_absolutize::serde::deserialize(StrDeserializer::<Error>::new("path/to/../123")).unwrap();
+let cache = AbsolutePathBuf::deserialize(StrDeserializer::<Error>::new("cache")).unwrap();
+
+assert!(input.is_absolute());
+assert!(cache.as_path().is_absolute());
+# }
+```
+
+## Clap Support
+
+Enabling the `clap` feature, which requires Rust 1.74 or later, adds `clap::AbsolutizeValueParser`, a `TypedValueParser` which absolutizes path arguments while they are parsed.
+
+```toml
+[dependencies.path-absolutize]
+version = "*"
+features = ["clap"]
+```
+
+```rust
+# #[cfg(feature = "clap")]
+# {
+use std::path::PathBuf;
+
+use clap::{Arg, Command};
+use path_absolutize::clap::AbsolutizeValueParser;
+
+let matches = Command::new("app")
+    .arg(Arg::new("out-dir").long("out-dir").value_parser(AbsolutizeValueParser::within("/srv/out")))
+    .get_matches_from(["app", "--out-dir", "build/../release"]);
+
+# if cfg!(unix) {
+assert_eq!("/srv/out/release", matches.get_one::<PathBuf>("out-dir").unwrap().to_str().unwrap());
+# }
+# }
+```
+
+## Camino Support
+
+Enabling the `camino` feature, which requires Rust 1.61 or later, adds the `Utf8Absolutize` trait, which works like `Absolutize` for `Utf8Path` and `Utf8PathBuf`.
+
+```toml
+[dependencies.path-absolutize]
+version = "*"
+features = ["camino"]
+```
+
+```rust
+# #[cfg(feature = "camino")]
+# {
+use camino::Utf8Path;
+use path_absolutize::Utf8Absolutize;
+
+let p = Utf8Path::new("path/to/../../../../123/456");
+
+# if cfg!(unix) {
+assert_eq!("/virtual/root/123/456", p.absolutize_virtually("/virtual/root").unwrap().as_str());
+# }
+# }
+```
+
+## Prefix Remapping
+
+`PrefixRemapper` replaces the longest matching prefix of absolutized paths, like rustc's `--remap-path-prefix`.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::PrefixRemapper;
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+let remapper = PrefixRemapper::new().rule("/home/ci", "/build").unwrap();
+
+assert_eq!(Path::new("/build/src/lib.rs"), remapper.remap(Path::new("/home/ci/src/../src/lib.rs")).unwrap());
+# }
+# }
+```
+
+## Virtual Paths of Real Paths
+
+`virtualize` is the inverse of `absolutize_virtually`.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::virtualize;
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+assert_eq!(Path::new("/a/b"), virtualize(Path::new("/srv/root/a/./b"), "/srv/root").unwrap());
+# }
+# }
+```
+
+## Mount Tables
+
+`MountTable` maps virtual paths, like the paths of URLs, onto several real directories by the longest matching mount point. Every mount point acts as its own jail.
+
+```rust
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::MountTable;
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+let table = MountTable::new()
+    .mount("/assets", "/srv/static").unwrap()
+    .mount("/uploads", "/data/uploads").unwrap();
+
+assert_eq!(Path::new("/srv/static/img/a.png"), table.resolve("/assets/css/../img/a.png").unwrap());
+assert_eq!(ErrorKind::InvalidInput, table.resolve("/assets/../uploads/a.png").unwrap_err().kind());
+# }
+# }
+```
+
+## Staging Roots
+
+The `Rebase` trait places absolutized paths under a staging root, like `DESTDIR` or a sysroot, and takes it back out.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::Rebase;
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+assert_eq!(Path::new("/tmp/pkg/usr/lib/x"), Path::new("/usr/lib/../lib/x").rebase_under("/tmp/pkg").unwrap());
+# }
+# }
+```
+
+## Explaining Resolution
+
+The `AbsolutizeExplained` trait returns the absolutized path with the CWD it was resolved from and the steps taken by the engine.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::{engine::explain::Step, AbsolutizeExplained};
+
+# if cfg!(unix) {
+let explanation = Path::new("/a/../..").absolutize_from_explained("/cwd").unwrap();
+
+assert_eq!(Some(&Step::ParentDirClamped), explanation.steps.last());
+# }
+```
+
+## Path Analysis
+
+`analyze` reports paths which are valid but suspicious, like `./config//app.toml`, with the byte span of each anomaly.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::{analyze, engine::analyze::AnomalyKind};
+
+let report = analyze(Path::new("./config//app.toml"));
+
+assert_eq!(AnomalyKind::RedundantSeparator, report.anomalies()[1].kind);
+```
+
+## Normalized Path Keys
+
+`NormalizedPathKey` is compared, hashed and ordered by the absolutized form of a path, so it can key maps and sets.
+
+```rust
+use std::collections::HashSet;
+
+use path_absolutize::NormalizedPathKey;
+
+# if cfg!(unix) {
+let keys: HashSet<NormalizedPathKey> = ["./a/b", "a/./b", "/cwd/a/b"]
+    .iter()
+    .map(|path| NormalizedPathKey::new_from(path, "/cwd").unwrap())
+    .collect();
+
+assert_eq!(1, keys.len());
+# }
+```
+
+## Path Tries
+
+`PathTrie` holds absolutized paths with values and finds which one covers another path in one walk of its components.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::PathTrie;
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+let mut trie = PathTrie::new();
+
+trie.insert("/srv", "read").unwrap();
+
+assert_eq!(Some((Path::new("/srv"), &"read")), trie.longest_prefix("/srv/www/../index.html").unwrap());
+# }
+# }
+```
+
+## Archive Entry Names
+
+`sanitize_archive_entry` keeps entry names of zip and tar archives, like `../../etc/x` or `C:\x`, inside the destination directory.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::{engine::archive::ArchivePolicy, sanitize_archive_entry};
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+assert_eq!(Path::new("/tmp/dest/etc/x"), sanitize_archive_entry("../../etc/x", "/tmp/dest", ArchivePolicy::Clamp).unwrap());
+# }
+# }
+```
+
+## Request Paths of Static File Servers
+
+`map_request_path` percent-decodes the path of an HTTP request and maps it to a file under a document root according to a `RequestPolicy`.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::{engine::http::RequestPolicy, map_request_path};
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+assert_eq!(Path::new("/srv/www/a b/c.txt"), map_request_path("/a%20b/./c.txt", "/srv/www", &RequestPolicy::new()).unwrap());
+# }
+# }
+```
+
+## Path Policies
+
+`PathPolicy` validates untrusted paths name by name, and `absolutize_virtually_with_policy` checks every name of the input, even one which a later `..` removes, before jailing the path, and returns the first violation as an error.
+
+```rust
+use std::path::Path;
+
+use path_absolutize::{absolutize_virtually_with_policy, engine::policy::PathPolicy};
+
+# if cfg!(unix) {
+# #[cfg(not(feature = "unsafe_cache"))]
+# {
+let policy = PathPolicy::web_upload();
+
+assert!(absolutize_virtually_with_policy(Path::new("a/.env"), "/srv/uploads", &policy).is_err());
+# }
+# }
+```
+
+## Verbatim Paths on Windows
+
+On Windows, the `Verbatim` trait converts paths to and from the `\\?\` verbatim form, which is not limited to `MAX_PATH` (260).
+
+```rust
+# #[cfg(windows)]
+# {
+use std::path::Path;
+
+use path_absolutize::Verbatim;
+
+assert_eq!(r"\\?\C:\path\123", Path::new(r"C:\path\to\..\123").to_verbatim().unwrap().to_str().unwrap());
+assert_eq!(r"C:\path\123", Path::new(r"\\?\C:\path\123").simplify_verbatim().to_str().unwrap());
+# }
+```
+
+## Win32 Name Quirks
+
+The Windows engine can validate and normalize names which Win32 treats specially, like `aux.txt` or `file. `.
+
+```rust
+use path_absolutize::engine::windows;
+
+assert!(windows::validate_win32(br"uploads\aux.txt").is_err());
+assert_eq!(br"uploads\file".as_ref(), windows::normalize_win32(br"uploads\file. ").as_ref());
+```
+
+## Separator Style
+
+The `separator_style` option of the Windows engine chooses the separator which joins the components.
+
+```rust
+use path_absolutize::engine::{windows, Options, SeparatorStyle};
+
+let options = Options::new().separator_style(SeparatorStyle::ForwardSlash);
+
+assert_eq!(b"C:/cwd/a/b".as_ref(), windows::absolutize_from_with(br"a\.\b", br"C:\cwd", &options).as_ref());
+```
+
+## MSYS2, Cygwin and WSL Paths
+
+The `engine::translate` module converts paths like `/c/Users/x`, `/cygdrive/c/Users/x` and `/mnt/c/Users/x` to and from Windows absolute paths.
+
+```rust
+use path_absolutize::engine::translate::{self, DriveMount};
+
+assert_eq!(Some(br"C:\Users\x".to_vec()), translate::posix_to_windows(b"../x", b"/c/Users/y", &DriveMount::msys2()));
+```
+
+## POSIX Double Slash Root
+
+By default, a leading `//` is collapsed to `/`. The `posix_double_slash_root` option keeps it as a root directory of its own.
+
+```rust
+use path_absolutize::engine::{posix, Options};
+
+let options = Options::new().posix_double_slash_root(true);
+
+assert_eq!(b"//host/x".as_ref(), posix::absolutize_from_with(b"//host/share/../x", b"/cwd", &options).as_ref());
+```
+
+## Limits
+
+`Limits` bounds the work done on adversarial paths, and `try_absolutize_from_with` of the engines fails at the first component over a limit.
+
+```rust
+use path_absolutize::engine::{posix, Error, Limit, Limits, Options};
+
+let options = Options::new().limits(Limits::new().max_parent_dirs(64));
+
+assert_eq!(Err(Error::LimitExceeded(Limit::ParentDirs)), posix::try_absolutize_from_with(&b"a/../".repeat(100), b"/cwd", &options));
+```
+
+## No-std Support
+
+The actual work is done by the engines in the `engine` module, which work on bytes, need only `alloc`, and handle both kinds of paths on any platform. Disable the default `std` feature to use them without `std`.
+
+```toml
+[dependencies.path-absolutize]
+version = "*"
+default-features = false
+```
+
+```rust
+use path_absolutize::engine::{posix, windows};
+
+assert_eq!(b"/cwd/path/to/456".as_ref(), posix::absolutize_from(b"path/to/123/../456", b"/cwd").as_ref());
+assert_eq!(br"C:\path\to\456".as_ref(), windows::absolutize_from(br"..\path\to\.\456", br"C:\cwd").as_ref());
+```
+
 ## Benchmark
 
 #### No-cache
@@ -407,6 +795,8 @@ cargo bench --features unsafe_cache
 
 */
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 #[cfg(any(
     all(feature = "lazy_static_cache", feature = "unsafe_cache"),
     all(feature = "once_cell_cache", feature = "unsafe_cache"),
@@ -414,8 +804,12 @@ cargo bench --features unsafe_cache
 ))]
 compile_error!("You can only enable at most one caching mechanism for `path-absolutize`.");
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub extern crate path_dedot;
 
+#[cfg(feature = "std")]
 use std::{
     borrow::Cow,
     io,
@@ -431,22 +825,97 @@ pub use path_dedot::update_cwd;
 ))]
 pub use path_dedot::CWD;
 
-mod absolutize;
+pub mod engine;
 
+#[cfg(feature = "std")]
+mod absolute_path_buf;
+#[cfg(feature = "std")]
+mod absolutize;
+#[cfg(feature = "std")]
+mod analyze;
+#[cfg(feature = "std")]
+mod archive;
+
+#[cfg(feature = "std")]
+mod http;
+#[cfg(feature = "std")]
 #[macro_use]
 mod macros;
 
-#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+#[cfg(feature = "std")]
+mod explain;
+#[cfg(feature = "std")]
+mod memo;
+#[cfg(feature = "std")]
+mod mount_table;
+#[cfg(feature = "std")]
+mod normalized_path_key;
+#[cfg(feature = "std")]
+mod path_trie;
+#[cfg(feature = "std")]
+mod policy;
+#[cfg(feature = "std")]
+mod rebase;
+#[cfg(feature = "std")]
+mod remap;
+#[cfg(all(
+    feature = "std",
+    any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm"))
+))]
 mod unix;
 
-#[cfg(windows)]
+#[cfg(all(feature = "std", windows))]
+mod verbatim;
+#[cfg(feature = "std")]
+mod virtualize;
+#[cfg(all(feature = "std", windows))]
 mod windows;
 
-pub use absolutize::*;
+#[cfg(feature = "camino")]
+mod camino;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "serde")]
+pub mod serde;
 
+#[cfg(feature = "std")]
+pub use absolute_path_buf::*;
+#[cfg(feature = "std")]
+pub use absolutize::*;
+#[cfg(feature = "std")]
+pub use analyze::*;
+#[cfg(feature = "std")]
+pub use archive::*;
+#[cfg(feature = "std")]
+pub use explain::*;
+#[cfg(feature = "std")]
+pub use http::*;
+#[cfg(feature = "std")]
+pub use memo::*;
+#[cfg(feature = "std")]
+pub use mount_table::*;
+#[cfg(feature = "std")]
+pub use normalized_path_key::*;
+#[cfg(feature = "std")]
+pub use path_trie::*;
+#[cfg(feature = "std")]
+pub use policy::*;
+#[cfg(feature = "std")]
+pub use rebase::*;
+#[cfg(feature = "std")]
+pub use remap::*;
+#[cfg(all(feature = "std", windows))]
+pub use verbatim::*;
+#[cfg(feature = "std")]
+pub use virtualize::*;
+
+#[cfg(feature = "camino")]
+pub use self::camino::*;
+
+#[cfg(feature = "std")]
 impl Absolutize for PathBuf {
     #[inline]
-    fn absolutize(&self) -> io::Result<Cow<Path>> {
+    fn absolutize(&self) -> io::Result<Cow<'_, Path>> {
         self.as_path().absolutize()
     }
 
@@ -456,7 +925,7 @@ impl Absolutize for PathBuf {
     }
 
     #[inline]
-    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<Path>> {
+    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
         self.as_path().absolutize_virtually(virtual_root)
     }
 }
@@ -19,6 +19,6 @@ macro_rules! get_cwd {
 #[cfg(feature = "unsafe_cache")]
 macro_rules! get_cwd {
     () => {
-        unsafe { $crate::CWD.as_path() }
+        unsafe { (*std::ptr::addr_of!($crate::CWD)).as_path() }
     };
 }
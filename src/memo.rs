@@ -0,0 +1,303 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+const NIL: usize = usize::MAX;
+
+/// The current directory of a drive, which a drive-relative path like `D:a` on Windows is resolved from.
+type DriveCwd = Option<(u8, Vec<u8>)>;
+
+#[derive(Debug)]
+struct Entry {
+    input:     Arc<Path>,
+    drive_cwd: DriveCwd,
+    output:    Arc<Path>,
+    prev:      usize,
+    next:      usize,
+}
+
+/// A least recently used cache of the results of one generation of the current working directory. The entries are kept in a slab and linked from the most recently used one (`head`) to the least recently used one (`tail`).
+#[derive(Debug)]
+struct MemoState {
+    cwd:         Arc<Path>,
+    follows_cwd: bool,
+    generation:  u64,
+    index:       HashMap<Arc<Path>, usize>,
+    entries:     Vec<Entry>,
+    head:        usize,
+    tail:        usize,
+}
+
+impl MemoState {
+    #[inline]
+    fn new(cwd: Arc<Path>, follows_cwd: bool) -> Self {
+        MemoState {
+            cwd,
+            follows_cwd,
+            generation: 0,
+            index: HashMap::new(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = (self.entries[i].prev, self.entries[i].next);
+
+        match prev {
+            NIL => self.head = next,
+            _ => self.entries[prev].next = next,
+        }
+
+        match next {
+            NIL => self.tail = prev,
+            _ => self.entries[next].prev = prev,
+        }
+    }
+
+    fn link_front(&mut self, i: usize) {
+        self.entries[i].prev = NIL;
+        self.entries[i].next = self.head;
+
+        match self.head {
+            NIL => self.tail = i,
+            head => self.entries[head].prev = i,
+        }
+
+        self.head = i;
+    }
+
+    /// Get the result of `input`. A result of a drive-relative path is a miss if the current directory of the drive has changed.
+    fn get(&mut self, input: &Path, drive_cwd: &DriveCwd) -> Option<Arc<Path>> {
+        let i = *self.index.get(input)?;
+
+        if self.entries[i].drive_cwd != *drive_cwd {
+            return None;
+        }
+
+        if self.head != i {
+            self.unlink(i);
+            self.link_front(i);
+        }
+
+        Some(self.entries[i].output.clone())
+    }
+
+    fn insert(
+        &mut self,
+        input: Arc<Path>,
+        drive_cwd: DriveCwd,
+        output: Arc<Path>,
+        capacity: usize,
+    ) {
+        if let Some(&i) = self.index.get(&input) {
+            // another thread has inserted the same input, or the current directory of its drive has changed
+            self.entries[i].drive_cwd = drive_cwd;
+            self.entries[i].output = output;
+
+            return;
+        }
+
+        let i = if self.entries.len() < capacity {
+            self.entries.push(Entry {
+                input: input.clone(),
+                drive_cwd,
+                output,
+                prev: NIL,
+                next: NIL,
+            });
+
+            self.entries.len() - 1
+        } else {
+            // reuse the slot of the least recently used entry
+            let i = self.tail;
+
+            self.unlink(i);
+            self.index.remove(&self.entries[i].input);
+
+            self.entries[i].input = input.clone();
+            self.entries[i].drive_cwd = drive_cwd;
+            self.entries[i].output = output;
+
+            i
+        };
+
+        self.index.insert(input, i);
+        self.link_front(i);
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.index.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Replace the current working directory, which starts a new generation and drops every entry, if it changes.
+    #[inline]
+    fn set_cwd(&mut self, cwd: &Path) {
+        if self.cwd.as_ref() != cwd {
+            self.cwd = Arc::from(cwd);
+            self.generation += 1;
+
+            self.clear();
+        }
+    }
+}
+
+/// An opt-in cache of absolutized paths for programs which absolutize the same relative paths again and again, like build tools. It remembers at most `capacity` results, dropping the least recently used one first, and returns them as `Arc<Path>`, so a hit costs a lookup instead of a normalization and an allocation.
+///
+/// The results depend on the current working directory. An absolutizer created by `new` follows it: each call of `absolutize` with a relative path reads it from the same source as `Absolutize::absolutize` (`std::env::current_dir` or the cache of a feature, which `update_cwd` of this crate updates with `unsafe_cache`) and compares it with the snapshot which the results are computed from. Each snapshot is a generation, and a new one drops every result, so a result computed from an older generation by another thread at the same time is never stored. On Windows, the results of drive-relative paths like `D:a` are also keyed by the current directory of their drive. The absolutizer is thread-safe.
+#[derive(Debug)]
+pub struct MemoAbsolutizer {
+    capacity: usize,
+    state:    Mutex<MemoState>,
+}
+
+impl MemoAbsolutizer {
+    /// Create an absolutizer which remembers at most `capacity` results and follows the current working directory.
+    #[inline]
+    pub fn new(capacity: usize) -> io::Result<Self> {
+        let cwd = get_cwd!();
+
+        Ok(Self::with_state(capacity, MemoState::new(Arc::from(Path::new(cwd.as_os_str())), true)))
+    }
+
+    /// Create an absolutizer which remembers at most `capacity` results, resolving relative paths from `cwd`.
+    #[inline]
+    pub fn with_cwd(capacity: usize, cwd: impl Into<PathBuf>) -> Self {
+        Self::with_state(capacity, MemoState::new(Arc::from(cwd.into()), false))
+    }
+
+    #[inline]
+    fn with_state(capacity: usize, state: MemoState) -> Self {
+        MemoAbsolutizer {
+            capacity,
+            state: Mutex::new(state),
+        }
+    }
+
+    #[inline]
+    fn state(&self) -> MutexGuard<'_, MemoState> {
+        // the state is consistent between the operations, so a panic in another thread does not break it
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Get an absolute path the same as `absolutize` does, or `absolutize_from` does with the directory given to `with_cwd` or `set_cwd`, from the cache if possible.
+    pub fn absolutize(&self, path: impl AsRef<Path>) -> io::Result<Arc<Path>> {
+        let path = path.as_ref();
+
+        let bytes = host::to_bytes(path.as_os_str());
+
+        let drive_cwd = host::drive_cwd(&bytes);
+
+        let lookup = if path.is_absolute() || !self.state().follows_cwd {
+            self.lookup(path, &drive_cwd, None)
+        } else {
+            let current = get_cwd!();
+
+            self.lookup(path, &drive_cwd, Some(Path::new(current.as_os_str())))
+        };
+
+        let (cwd, generation) = match lookup {
+            Ok(miss) => miss,
+            Err(output) => return Ok(output),
+        };
+
+        // normalize without holding the lock
+        let cwd = host::to_bytes(cwd.as_os_str());
+
+        let output: Arc<Path> = Arc::from(
+            host::from_engine(
+                path,
+                host::absolutize_with_drive_cwd(&bytes, &cwd, drive_cwd.clone()),
+            )
+            .as_ref(),
+        );
+
+        if self.capacity > 0 {
+            let mut state = self.state();
+
+            if state.generation == generation {
+                state.insert(Arc::from(path), drive_cwd, output.clone(), self.capacity);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Look up the result of `path` after following `current`, the current working directory which has just been read. Returns `Err` with the result on a hit, or `Ok` with the snapshot of the current working directory and its generation on a miss.
+    #[inline]
+    fn lookup(
+        &self,
+        path: &Path,
+        drive_cwd: &DriveCwd,
+        current: Option<&Path>,
+    ) -> Result<(Arc<Path>, u64), Arc<Path>> {
+        let mut state = self.state();
+
+        if let Some(current) = current {
+            state.set_cwd(current);
+        }
+
+        match state.get(path, drive_cwd) {
+            Some(output) => Err(output),
+            None => Ok((state.cwd.clone(), state.generation)),
+        }
+    }
+
+    /// Get the snapshot of the current working directory which the results are computed from.
+    #[inline]
+    pub fn cwd(&self) -> Arc<Path> {
+        self.state().cwd.clone()
+    }
+
+    /// Get the generation of the current working directory, which increases each time the directory changes.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.state().generation
+    }
+
+    /// Resolve relative paths from `cwd` instead of following the current working directory. If it is different from the snapshot, a new generation starts and every result is dropped.
+    #[inline]
+    pub fn set_cwd(&self, cwd: impl AsRef<Path>) {
+        let mut state = self.state();
+
+        state.follows_cwd = false;
+        state.set_cwd(cwd.as_ref());
+    }
+
+    /// Drop every result.
+    #[inline]
+    pub fn clear(&self) {
+        self.state().clear();
+    }
+
+    /// Get the maximum number of results.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of results.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Whether there is no result.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
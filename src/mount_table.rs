@@ -0,0 +1,124 @@
+use std::{
+    ffi::OsStr,
+    io::{self, ErrorKind},
+    path::{Component, Path, PathBuf},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+use crate::{remap::replace_prefix, Absolutize};
+
+/// Map virtual paths, like the paths of URLs, onto several real directories, like `/assets` onto `/srv/static` and `/uploads` onto `/data/uploads`.
+///
+/// A virtual path is resolved by the mount point which is its longest leading part, compared component by component and ignoring case on Windows. Every mount point acts as its own virtual root, so `..` cannot leave the real directory of the mount, let alone reach another mount.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MountTable {
+    mounts: Vec<(PathBuf, PathBuf)>,
+}
+
+impl MountTable {
+    /// Create a table without mounts.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount the real directory `real_root` at the virtual path `mount_point`. `mount_point` is always resolved from `/`, so `assets` means `/assets`, and `real_root` is absolutized.
+    pub fn mount(
+        mut self,
+        mount_point: impl AsRef<Path>,
+        real_root: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let mount_point = Path::new("/").join(mount_point).absolutize_from("/")?.into_owned();
+        let real_root = real_root.as_ref().absolutize()?.into_owned();
+
+        self.mounts.push((mount_point, real_root));
+
+        Ok(self)
+    }
+
+    /// Iterate over the mount points and their real directories in order.
+    #[inline]
+    pub fn mounts(&self) -> impl Iterator<Item = (&Path, &Path)> {
+        self.mounts
+            .iter()
+            .map(|(mount_point, real_root)| (mount_point.as_path(), real_root.as_path()))
+    }
+
+    /// Resolve a virtual path into a real path. The virtual path is always resolved from `/`, and the rest of it after the mount point is absolutized virtually under the real directory of the mount.
+    ///
+    /// Returns an error of `ErrorKind::NotFound` if no mount point matches, and an error of `ErrorKind::InvalidInput` if the path leaves the real directory of its mount or has a prefix.
+    pub fn resolve(&self, virtual_path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let virtual_path = virtual_path.as_ref();
+
+        if let Some(Component::Prefix(_)) = virtual_path.components().next() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "The virtual path must not have a prefix.",
+            ));
+        }
+
+        let mut names = virtual_path.components();
+
+        while let Some(Component::RootDir | Component::CurDir) = names.clone().next() {
+            names.next();
+        }
+
+        let mut best: Option<(usize, &Path, &Path)> = None;
+
+        for (mount_point, real_root) in self.mounts.iter() {
+            let mut rest = names.clone();
+
+            let mut length = 0;
+
+            let matched = mount_point.components().all(|component| match component {
+                Component::Normal(name) => {
+                    length += 1;
+
+                    match rest.next() {
+                        Some(Component::Normal(other)) => name_key(name) == name_key(other),
+                        _ => false,
+                    }
+                },
+                _ => true,
+            });
+
+            if matched && best.map(|(best_length, ..)| length > best_length).unwrap_or(true) {
+                best = Some((length, rest.as_path(), real_root));
+            }
+        }
+
+        let (_, rest, real_root) = best.ok_or_else(|| {
+            io::Error::new(ErrorKind::NotFound, "The virtual path is not under any mount point.")
+        })?;
+
+        // join the rest first, so that a leading `..` is resolved from the real directory instead of the current working directory
+        let joined = real_root.join(rest);
+
+        Ok(joined.absolutize_virtually(real_root)?.into_owned())
+    }
+
+    /// Map a real path back to its virtual path by the mount whose real directory is its longest leading part. The real path is absolutized.
+    ///
+    /// Returns an error of `ErrorKind::NotFound` if the path is not under any real directory.
+    pub fn to_virtual(&self, real_path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let real_path = real_path.as_ref().absolutize()?;
+
+        let mounts = self
+            .mounts
+            .iter()
+            .map(|(mount_point, real_root)| (real_root.as_path(), mount_point.as_path()));
+
+        replace_prefix(&real_path, mounts).ok_or_else(|| {
+            io::Error::new(ErrorKind::NotFound, "The real path is not under any mounted directory.")
+        })
+    }
+}
+
+/// Get the key of a name of a mount point which is compared, ignoring case on Windows.
+#[inline]
+fn name_key(name: &OsStr) -> Vec<u8> {
+    host::path_key(&host::to_bytes(name))
+}
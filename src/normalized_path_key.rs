@@ -0,0 +1,175 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    hash::{BuildHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+use crate::Absolutize;
+
+/// A key of a path which is absolutized once when it is created, and compared, hashed and ordered by the absolutized form, so `./a/b`, `a/./b` and `/cwd/a/b` are the same key if the current working directory is `/cwd`. On Windows, the absolutized forms are compared ignoring case, so `C:\A` and `c:\a` are the same key. The original path is kept for display.
+#[derive(Debug, Clone)]
+pub struct NormalizedPathKey {
+    original:   PathBuf,
+    normalized: PathBuf,
+    key:        Vec<u8>,
+}
+
+impl NormalizedPathKey {
+    /// Create a key by absolutizing `path` from the current working directory.
+    #[inline]
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        let normalized = path.absolutize()?.into_owned();
+
+        Ok(Self::from_normalized(path.to_path_buf(), normalized))
+    }
+
+    /// Create a key by absolutizing `path` from `cwd`.
+    #[inline]
+    pub fn new_from(path: impl AsRef<Path>, cwd: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        let normalized = path.absolutize_from(cwd)?.into_owned();
+
+        Ok(Self::from_normalized(path.to_path_buf(), normalized))
+    }
+
+    #[inline]
+    fn from_normalized(original: PathBuf, normalized: PathBuf) -> Self {
+        let key = host::path_key(&host::to_bytes(normalized.as_os_str()));
+
+        NormalizedPathKey {
+            original,
+            normalized,
+            key,
+        }
+    }
+
+    /// Get the original path.
+    #[inline]
+    pub fn original(&self) -> &Path {
+        &self.original
+    }
+
+    /// Get the absolutized path.
+    #[inline]
+    pub fn normalized(&self) -> &Path {
+        &self.normalized
+    }
+
+    /// Unwrap the original path.
+    #[inline]
+    pub fn into_original(self) -> PathBuf {
+        self.original
+    }
+}
+
+impl PartialEq for NormalizedPathKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for NormalizedPathKey {}
+
+impl Hash for NormalizedPathKey {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
+    }
+}
+
+impl PartialOrd for NormalizedPathKey {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NormalizedPathKey {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl Display for NormalizedPathKey {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.original.display(), f)
+    }
+}
+
+/// Let `HashMap` and `BTreeMap` keyed by `NormalizedPathKey` be used with raw paths, which are absolutized from the current working directory for each call.
+pub trait NormalizedPathMap<V> {
+    /// Insert a value with the key of `path`. If the map already has the key, the value is replaced and returned, and the original path of the key is kept.
+    fn insert_path(&mut self, path: impl AsRef<Path>, value: V) -> io::Result<Option<V>>;
+
+    /// Get the value of the key of `path`.
+    fn get_path(&self, path: impl AsRef<Path>) -> io::Result<Option<&V>>;
+
+    /// Get the value of the key of `path` mutably.
+    fn get_path_mut(&mut self, path: impl AsRef<Path>) -> io::Result<Option<&mut V>>;
+
+    /// Whether the map has the key of `path`.
+    #[inline]
+    fn contains_path(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        Ok(self.get_path(path)?.is_some())
+    }
+
+    /// Remove the value of the key of `path`.
+    fn remove_path(&mut self, path: impl AsRef<Path>) -> io::Result<Option<V>>;
+}
+
+impl<V, S: BuildHasher> NormalizedPathMap<V> for HashMap<NormalizedPathKey, V, S> {
+    #[inline]
+    fn insert_path(&mut self, path: impl AsRef<Path>, value: V) -> io::Result<Option<V>> {
+        Ok(self.insert(NormalizedPathKey::new(path)?, value))
+    }
+
+    #[inline]
+    fn get_path(&self, path: impl AsRef<Path>) -> io::Result<Option<&V>> {
+        Ok(self.get(&NormalizedPathKey::new(path)?))
+    }
+
+    #[inline]
+    fn get_path_mut(&mut self, path: impl AsRef<Path>) -> io::Result<Option<&mut V>> {
+        Ok(self.get_mut(&NormalizedPathKey::new(path)?))
+    }
+
+    #[inline]
+    fn remove_path(&mut self, path: impl AsRef<Path>) -> io::Result<Option<V>> {
+        Ok(self.remove(&NormalizedPathKey::new(path)?))
+    }
+}
+
+impl<V> NormalizedPathMap<V> for BTreeMap<NormalizedPathKey, V> {
+    #[inline]
+    fn insert_path(&mut self, path: impl AsRef<Path>, value: V) -> io::Result<Option<V>> {
+        Ok(self.insert(NormalizedPathKey::new(path)?, value))
+    }
+
+    #[inline]
+    fn get_path(&self, path: impl AsRef<Path>) -> io::Result<Option<&V>> {
+        Ok(self.get(&NormalizedPathKey::new(path)?))
+    }
+
+    #[inline]
+    fn get_path_mut(&mut self, path: impl AsRef<Path>) -> io::Result<Option<&mut V>> {
+        Ok(self.get_mut(&NormalizedPathKey::new(path)?))
+    }
+
+    #[inline]
+    fn remove_path(&mut self, path: impl AsRef<Path>) -> io::Result<Option<V>> {
+        Ok(self.remove(&NormalizedPathKey::new(path)?))
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+use crate::Absolutize;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    value:    Option<(PathBuf, V)>,
+    children: BTreeMap<Vec<u8>, Node<V>>,
+}
+
+impl<V> Node<V> {
+    #[inline]
+    fn new() -> Self {
+        Node {
+            value: None, children: BTreeMap::new()
+        }
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+
+    /// Remove the value at `keys` under this node, and the nodes which become empty.
+    fn remove(&mut self, keys: &[Vec<u8>]) -> Option<(PathBuf, V)> {
+        match keys.split_first() {
+            None => self.value.take(),
+            Some((key, rest)) => {
+                let child = self.children.get_mut(key)?;
+
+                let removed = child.remove(rest);
+
+                if child.is_empty() {
+                    self.children.remove(key);
+                }
+
+                removed
+            },
+        }
+    }
+}
+
+/// A trie of paths with values, which answers which path covers another path, like which rule of a permission system covers a requested path, in the time of walking the components of the requested path.
+///
+/// Paths are absolutized from the current working directory, and their components are compared by the same rules as the engine which this crate uses on the host, so on Windows they are compared ignoring case and `C:\A` covers `c:\a\b`.
+#[derive(Debug, Clone)]
+pub struct PathTrie<V> {
+    root:   Node<V>,
+    length: usize,
+}
+
+impl<V> PathTrie<V> {
+    /// Create an empty trie.
+    #[inline]
+    pub fn new() -> Self {
+        PathTrie {
+            root: Node::new(), length: 0
+        }
+    }
+
+    /// Get the number of paths.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Whether the trie has no path.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    #[inline]
+    fn keys(path: &Path) -> io::Result<(PathBuf, Vec<Vec<u8>>)> {
+        let path = path.absolutize()?.into_owned();
+
+        let keys = host::component_keys(&host::to_bytes(path.as_os_str()));
+
+        Ok((path, keys))
+    }
+
+    #[inline]
+    fn find(&self, keys: &[Vec<u8>]) -> Option<&Node<V>> {
+        keys.iter().try_fold(&self.root, |node, key| node.children.get(key))
+    }
+
+    /// Insert a path with a value. If the trie already has the path, the value is replaced and returned, and the absolutized path is replaced as well.
+    pub fn insert(&mut self, path: impl AsRef<Path>, value: V) -> io::Result<Option<V>> {
+        let (path, keys) = Self::keys(path.as_ref())?;
+
+        let node = keys
+            .into_iter()
+            .fold(&mut self.root, |node, key| node.children.entry(key).or_insert_with(Node::new));
+
+        let old = node.value.replace((path, value)).map(|(_, value)| value);
+
+        if old.is_none() {
+            self.length += 1;
+        }
+
+        Ok(old)
+    }
+
+    /// Get the value of a path.
+    #[inline]
+    pub fn get(&self, path: impl AsRef<Path>) -> io::Result<Option<&V>> {
+        let (_, keys) = Self::keys(path.as_ref())?;
+
+        Ok(self.find(&keys).and_then(|node| node.value.as_ref()).map(|(_, value)| value))
+    }
+
+    /// Remove a path and get its value.
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> io::Result<Option<V>> {
+        let (_, keys) = Self::keys(path.as_ref())?;
+
+        let removed = self.root.remove(&keys).map(|(_, value)| value);
+
+        if removed.is_some() {
+            self.length -= 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Get the longest path in the trie which `path` starts with, compared component by component, i.e., the closest path which covers `path`, with its value. The path itself counts.
+    pub fn longest_prefix(&self, path: impl AsRef<Path>) -> io::Result<Option<(&Path, &V)>> {
+        let (_, keys) = Self::keys(path.as_ref())?;
+
+        let mut node = &self.root;
+        let mut longest = node.value.as_ref();
+
+        for key in keys.iter() {
+            match node.children.get(key) {
+                Some(child) => {
+                    node = child;
+
+                    if node.value.is_some() {
+                        longest = node.value.as_ref();
+                    }
+                },
+                None => break,
+            }
+        }
+
+        Ok(longest.map(|(path, value)| (path.as_path(), value)))
+    }
+
+    /// Whether the trie has any path which starts with `path`, i.e., `path` itself or any path under it.
+    #[inline]
+    pub fn contains_subtree(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        let (_, keys) = Self::keys(path.as_ref())?;
+
+        // empty nodes are removed, so every node has a value or a descendant with a value
+        Ok(self.find(&keys).map(|node| !node.is_empty()).unwrap_or(false))
+    }
+
+    /// Iterate over the absolutized paths and the values, where a path comes before the paths under it.
+    #[inline]
+    pub fn iter(&self) -> PathTrieIter<'_, V> {
+        PathTrieIter {
+            pending: Some(&self.root), stack: Vec::new()
+        }
+    }
+}
+
+impl<V> Default for PathTrie<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator over the paths and the values of a `PathTrie`. See `PathTrie::iter`.
+#[derive(Debug)]
+pub struct PathTrieIter<'a, V> {
+    pending: Option<&'a Node<V>>,
+    stack:   Vec<btree_map::Values<'a, Vec<u8>, Node<V>>>,
+}
+
+impl<'a, V> Iterator for PathTrieIter<'a, V> {
+    type Item = (&'a Path, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.pending.take() {
+                self.stack.push(node.children.values());
+
+                if let Some((path, value)) = &node.value {
+                    return Some((path.as_path(), value));
+                }
+            }
+
+            match self.stack.last_mut()?.next() {
+                Some(node) => self.pending = Some(node),
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a PathTrie<V> {
+    type IntoIter = PathTrieIter<'a, V>;
+    type Item = (&'a Path, &'a V);
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
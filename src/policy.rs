@@ -0,0 +1,39 @@
+use std::{borrow::Cow, io, path::Path};
+
+use crate::engine::policy::{PathPolicy, PolicyViolationKind};
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+/// Find every name in a path which `policy` rejects, as pairs of the index of the component and the reason. The components are counted the same way as `Path::components` does, including the prefix and the root directory.
+pub fn path_policy_violations(
+    path: &Path,
+    policy: &PathPolicy,
+) -> Vec<(usize, PolicyViolationKind)> {
+    let path = host::to_bytes(path.as_os_str());
+
+    host::engine::policy_violations(&path, policy)
+        .into_iter()
+        .map(|violation| (violation.index, violation.kind))
+        .collect()
+}
+
+/// Get an absolute path **only under a specific directory**, the same as `absolutize_virtually` does, after checking the names of the path with `policy`.
+///
+/// The names are checked as they are written, before `..` is resolved, so `.env/../a` is rejected by a policy which rejects hidden names. Returns an error of `ErrorKind::InvalidInput` for the first violation of the policy only; use `path_policy_violations` to find all of them.
+pub fn absolutize_virtually_with_policy<'a>(
+    path: &'a Path,
+    virtual_root: impl AsRef<Path>,
+    policy: &PathPolicy,
+) -> io::Result<Cow<'a, Path>> {
+    let cwd = get_cwd!();
+
+    let bytes = host::to_bytes(path.as_os_str());
+    let virtual_root = host::to_bytes(virtual_root.as_ref().as_os_str());
+    let cwd = host::to_bytes(cwd.as_os_str());
+
+    let result = host::absolutize_virtually_with_policy(&bytes, &virtual_root, &cwd, policy)?;
+
+    Ok(host::from_engine(path, result))
+}
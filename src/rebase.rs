@@ -0,0 +1,51 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{virtualize, Absolutize};
+
+/// Let `Path` and `PathBuf` be placed under and taken out of a staging root, like `DESTDIR` or a sysroot.
+pub trait Rebase {
+    /// Get the absolute path placed under `staging_root`, i.e., `/usr/lib/x` under `/tmp/pkg` becomes `/tmp/pkg/usr/lib/x`. Both paths are absolutized first. On Windows, the prefix of the path, like `C:`, is dropped, so `C:\Program Files\x` under `D:\stage` becomes `D:\stage\Program Files\x`.
+    fn rebase_under(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf>;
+
+    /// Get the absolute path with `staging_root` taken out, i.e., `/tmp/pkg/usr/lib/x` with `/tmp/pkg` taken out becomes `/usr/lib/x`. Both paths are absolutized first. On Windows, the result has no prefix, like `\Program Files\x`, and `staging_root` is compared ignoring case.
+    ///
+    /// Returns an error of `ErrorKind::InvalidInput` if the path is not under `staging_root`.
+    fn strip_staging_root(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf>;
+}
+
+impl Rebase for Path {
+    fn rebase_under(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let path = self.absolutize()?;
+
+        let mut rebased = staging_root.as_ref().absolutize()?.into_owned();
+
+        for component in path.components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir => (),
+                _ => rebased.push(component),
+            }
+        }
+
+        Ok(rebased)
+    }
+
+    #[inline]
+    fn strip_staging_root(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf> {
+        Ok(virtualize(self, staging_root)?.into_owned())
+    }
+}
+
+impl Rebase for PathBuf {
+    #[inline]
+    fn rebase_under(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf> {
+        self.as_path().rebase_under(staging_root)
+    }
+
+    #[inline]
+    fn strip_staging_root(&self, staging_root: impl AsRef<Path>) -> io::Result<PathBuf> {
+        self.as_path().strip_staging_root(staging_root)
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    borrow::Cow,
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+use crate::Absolutize;
+
+/// Remap the prefixes of paths with an ordered list of `from => to` rules, like rustc's `--remap-path-prefix`, so that paths embedded in build outputs do not depend on the machine.
+///
+/// Prefixes are matched component by component, so `/home/ci` matches `/home/ci/src` but not `/home/ci2`. On Windows, they are also matched case-insensitively. If several rules match, the one with the longest prefix wins, and the earliest one wins among rules with prefixes of the same length.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixRemapper {
+    rules: Vec<(PathBuf, PathBuf)>,
+}
+
+impl PrefixRemapper {
+    /// Create a remapper without rules.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule which replaces the prefix `from` with `to`. `from` is absolutized, and `to` is kept as it is, so it can be relative, like `.`, or a placeholder, like `/build`.
+    #[inline]
+    pub fn rule(mut self, from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<Self> {
+        let from = from.as_ref().absolutize()?.into_owned();
+
+        self.rules.push((from, to.as_ref().to_path_buf()));
+
+        Ok(self)
+    }
+
+    /// Iterate over the rules in order.
+    #[inline]
+    pub fn rules(&self) -> impl Iterator<Item = (&Path, &Path)> {
+        self.rules.iter().map(|(from, to)| (from.as_path(), to.as_path()))
+    }
+
+    /// Absolutize a path and replace its prefix with the `to` of the best matching rule. The absolute path is returned as it is if no rule matches.
+    pub fn remap<'a>(&self, path: &'a Path) -> io::Result<Cow<'a, Path>> {
+        let path = path.absolutize()?;
+
+        let rules = self.rules.iter().map(|(from, to)| (from.as_path(), to.as_path()));
+
+        Ok(match replace_prefix(&path, rules) {
+            Some(remapped) => Cow::Owned(remapped),
+            None => path,
+        })
+    }
+
+    /// Reverse `remap`, i.e., replace the prefix of a remapped path with the `from` of the best matching rule, comparing the path with the `to` of each rule. The path is not absolutized, and it is returned as it is if no rule matches.
+    pub fn unmap<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        let rules = self.rules.iter().map(|(from, to)| (to.as_path(), from.as_path()));
+
+        match replace_prefix(path, rules) {
+            Some(unmapped) => Cow::Owned(unmapped),
+            None => Cow::Borrowed(path),
+        }
+    }
+}
+
+/// Replace the longest prefix of `path` which matches the first path of a rule with the second path of that rule.
+pub(crate) fn replace_prefix<'a>(
+    path: &Path,
+    rules: impl Iterator<Item = (&'a Path, &'a Path)>,
+) -> Option<PathBuf> {
+    let mut best: Option<(usize, &Path)> = None;
+
+    for (prefix, replacement) in rules {
+        if !host::starts_with(path, prefix) {
+            continue;
+        }
+
+        let length = prefix.components().count();
+
+        if best.map(|(best_length, _)| length > best_length).unwrap_or(true) {
+            best = Some((length, replacement));
+        }
+    }
+
+    let (length, replacement) = best?;
+
+    let mut rest = path.components();
+
+    for _ in 0..length {
+        rest.next();
+    }
+
+    let rest = rest.as_path();
+
+    let mut replaced = replacement.to_path_buf();
+
+    if !rest.as_os_str().is_empty() {
+        replaced.push(rest);
+    }
+
+    Some(replaced)
+}
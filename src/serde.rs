@@ -0,0 +1,202 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Formatter},
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, Visitor},
+    ser::{self, Serializer},
+    Deserialize, Serialize,
+};
+
+use crate::{AbsolutePathBuf, Absolutize};
+
+/// Serialize a path as a string. Paths which are not valid UTF-8 cannot be serialized.
+///
+/// Use it with `#[serde(serialize_with = "path_absolutize::serde::serialize")]`.
+#[inline]
+pub fn serialize<P: AsRef<Path>, S: Serializer>(
+    path: &P,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match path.as_ref().to_str() {
+        Some(s) => serializer.serialize_str(s),
+        None => Err(ser::Error::custom("path contains invalid UTF-8 characters")),
+    }
+}
+
+/// Deserialize a path and absolutize it against the current working directory. Byte buffers are also accepted, as raw bytes on Unix-like systems and as UTF-8 on other platforms.
+///
+/// Use it with `#[serde(deserialize_with = "path_absolutize::serde::deserialize")]`.
+#[inline]
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    AbsolutizeSeed::new().deserialize(deserializer)
+}
+
+/// The `Option` counterparts of `serialize` and `deserialize`.
+///
+/// Use it with `#[serde(with = "path_absolutize::serde::option")]`.
+pub mod option {
+    use std::path::{Path, PathBuf};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serialize an optional path as a string or `None`.
+    #[inline]
+    pub fn serialize<P: AsRef<Path>, S: Serializer>(
+        path: &Option<P>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match path {
+            Some(path) => serializer.serialize_some(&super::Wrapper(path.as_ref())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize an optional path and absolutize it against the current working directory.
+    #[inline]
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PathBuf>, D::Error> {
+        let path: Option<super::Absolutized> = Option::deserialize(deserializer)?;
+
+        Ok(path.map(|path| path.0))
+    }
+}
+
+struct Wrapper<'a>(&'a Path);
+
+impl<'a> Serialize for Wrapper<'a> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+struct Absolutized(PathBuf);
+
+impl<'de> Deserialize<'de> for Absolutized {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Absolutized)
+    }
+}
+
+/// A `DeserializeSeed` which deserializes a path and absolutizes it against a base directory, such as the directory of the configuration file being loaded.
+///
+/// ```rust
+/// use std::path::Path;
+///
+/// use path_absolutize::serde::AbsolutizeSeed;
+/// use serde::de::{
+///     value::{Error, StrDeserializer},
+///     DeserializeSeed,
+/// };
+///
+/// let deserializer = StrDeserializer::<Error>::new("../data");
+///
+/// let path = AbsolutizeSeed::from_base("/etc/app")
+///     .unwrap()
+///     .deserialize(deserializer)
+///     .unwrap();
+///
+/// # if cfg!(unix) {
+/// assert_eq!(Path::new("/etc/data"), path);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AbsolutizeSeed<'a> {
+    base: Option<Cow<'a, Path>>,
+}
+
+impl<'a> AbsolutizeSeed<'a> {
+    /// Absolutize against the current working directory.
+    #[inline]
+    pub const fn new() -> AbsolutizeSeed<'static> {
+        AbsolutizeSeed {
+            base: None
+        }
+    }
+
+    /// Absolutize against the given base directory. A relative base is absolutized against the current working directory first.
+    #[inline]
+    pub fn from_base(base: &'a (impl AsRef<Path> + ?Sized)) -> io::Result<AbsolutizeSeed<'a>> {
+        Ok(AbsolutizeSeed {
+            base: Some(base.as_ref().absolutize()?)
+        })
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for AbsolutizeSeed<'a> {
+    type Value = PathBuf;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<PathBuf, D::Error> {
+        let path = deserializer.deserialize_string(PathVisitor)?;
+
+        let absolutized = match self.base {
+            Some(base) => path.absolutize_from(base.as_ref()),
+            None => path.absolutize(),
+        };
+
+        match absolutized {
+            Ok(absolutized) => Ok(absolutized.into_owned()),
+            Err(error) => Err(de::Error::custom(error)),
+        }
+    }
+}
+
+struct PathVisitor;
+
+impl<'de> Visitor<'de> for PathVisitor {
+    type Value = PathBuf;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a path string")
+    }
+
+    #[inline]
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<PathBuf, E> {
+        Ok(PathBuf::from(v))
+    }
+
+    #[inline]
+    fn visit_string<E: de::Error>(self, v: String) -> Result<PathBuf, E> {
+        Ok(PathBuf::from(v))
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<PathBuf, E> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        Ok(PathBuf::from(OsStr::from_bytes(v)))
+    }
+
+    #[cfg(not(unix))]
+    #[inline]
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<PathBuf, E> {
+        match std::str::from_utf8(v) {
+            Ok(s) => Ok(PathBuf::from(s)),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+impl Serialize for AbsolutePathBuf {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.as_path(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AbsolutePathBuf {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = deserializer.deserialize_string(PathVisitor)?;
+
+        AbsolutePathBuf::new(path).map_err(de::Error::custom)
+    }
+}
@@ -1,171 +1,175 @@
+#[cfg(unix)]
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+#[cfg(target_os = "wasi")]
+use std::os::wasi::ffi::{OsStrExt, OsStringExt};
 use std::{
     borrow::Cow,
-    ffi::OsString,
-    io::{self, ErrorKind},
-    path::{Component, Path, PathBuf},
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
 };
 
+pub(crate) use crate::engine::posix as engine;
 use crate::{
-    path_dedot::{ParseDot, MAIN_SEPARATOR},
+    engine::{policy::PathPolicy, Error, Options},
+    explain::Explained,
     Absolutize,
 };
 
-impl Absolutize for Path {
-    #[inline]
-    fn absolutize(&self) -> io::Result<Cow<Path>> {
-        let cwd = get_cwd!();
+/// Get the bytes which the engine works on.
+#[cfg(any(unix, target_os = "wasi"))]
+#[inline]
+pub(crate) fn to_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    Cow::Borrowed(s.as_bytes())
+}
 
-        self.absolutize_from(cwd)
+/// Get the bytes which the engine works on.
+#[cfg(not(any(unix, target_os = "wasi")))]
+#[inline]
+pub(crate) fn to_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    match s.to_string_lossy() {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
     }
+}
 
-    fn absolutize_from(&self, cwd: impl AsRef<Path>) -> io::Result<Cow<Path>> {
-        let mut iter = self.components();
-
-        let mut has_change = false;
-
-        if let Some(first_component) = iter.next() {
-            let mut tokens = Vec::new();
-
-            let first_is_root = match first_component {
-                Component::RootDir => {
-                    tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                    true
-                },
-                Component::CurDir => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
-
-                    for token in cwd.iter() {
-                        tokens.push(token);
-                    }
-
-                    !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str()
-                },
-                Component::ParentDir => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
-
-                    match cwd.parent() {
-                        Some(cwd_parent) => {
-                            for token in cwd_parent.iter() {
-                                tokens.push(token);
-                            }
-
-                            !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str()
-                        },
-                        None => {
-                            // don't care about `cwd` is "//" or "///"
-                            if cwd == MAIN_SEPARATOR.as_os_str() {
-                                tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                                true
-                            } else {
-                                false
-                            }
-                        },
-                    }
-                },
-                _ => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
+/// Convert bytes returned by the engine back into a path.
+#[cfg(any(unix, target_os = "wasi"))]
+#[inline]
+pub(crate) fn from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
 
-                    for token in cwd.iter() {
-                        tokens.push(token);
-                    }
+/// Convert bytes returned by the engine back into a path.
+#[cfg(not(any(unix, target_os = "wasi")))]
+#[inline]
+pub(crate) fn from_bytes(bytes: Vec<u8>) -> PathBuf {
+    match String::from_utf8(bytes) {
+        Ok(s) => PathBuf::from(s),
+        Err(err) => PathBuf::from(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+    }
+}
 
-                    let first_is_root =
-                        !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str();
+/// Convert a result of the engine into a path. A borrowed result is always the whole input path.
+#[inline]
+pub(crate) fn from_engine<'a>(path: &'a Path, result: Cow<'_, [u8]>) -> Cow<'a, Path> {
+    match result {
+        Cow::Borrowed(_) => Cow::Borrowed(path),
+        Cow::Owned(bytes) => Cow::Owned(from_bytes(bytes)),
+    }
+}
 
-                    tokens.push(first_component.as_os_str());
+/// Get an absolute path from the current working directory, the same as `Path::absolutize` does.
+#[inline]
+pub(crate) fn absolutize<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    engine::absolutize_from(path, cwd)
+}
 
-                    first_is_root
-                },
-            };
+/// Get the current directory of the drive of a drive-relative path. POSIX paths have no drives.
+#[inline]
+pub(crate) fn drive_cwd(_path: &[u8]) -> Option<(u8, Vec<u8>)> {
+    None
+}
 
-            for component in iter {
-                match component {
-                    Component::CurDir => {
-                        // may be unreachable
-                        has_change = true;
-                    },
-                    Component::ParentDir => {
-                        let tokens_length = tokens.len();
+/// Get an absolute path from the current working directory. POSIX paths have no drives, so `drive_cwd` is ignored.
+#[inline]
+pub(crate) fn absolutize_with_drive_cwd<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    _drive_cwd: Option<(u8, Vec<u8>)>,
+) -> Cow<'a, [u8]> {
+    absolutize(path, cwd)
+}
 
-                        if tokens_length > 0 && (tokens_length != 1 || !first_is_root) {
-                            tokens.remove(tokens_length - 1);
-                        }
+/// Get an absolute path from `cwd` and the steps taken to get it, the same as `AbsolutizeExplained` does. POSIX paths are always resolved from `cwd`, so `use_drive_cwds` is ignored and no other current working directory is returned.
+#[inline]
+pub(crate) fn explain<'a>(path: &'a [u8], cwd: &[u8], _use_drive_cwds: bool) -> Explained<'a> {
+    let (path, steps) = engine::explain_from_with(path, cwd, &Options::new());
 
-                        has_change = true;
-                    },
-                    _ => {
-                        tokens.push(component.as_os_str());
-                    },
-                }
-            }
+    (path, steps, None)
+}
 
-            let tokens_length = tokens.len();
+/// Get an absolute path under a virtual root, the same as `Path::absolutize_virtually` does.
+#[inline]
+pub(crate) fn absolutize_virtually<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::absolutize_virtually(path, virtual_root, cwd)
+}
 
-            debug_assert!(tokens_length > 0);
+/// Get an absolute path under a virtual root, checking the names of the path with a policy first.
+#[inline]
+pub(crate) fn absolutize_virtually_with_policy<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    policy: &PathPolicy,
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::absolutize_virtually_with(
+        path,
+        virtual_root,
+        cwd,
+        &Options::new().path_policy(policy.clone()),
+    )
+}
 
-            let mut size = tokens.iter().fold(tokens_length - 1, |acc, &x| acc + x.len());
+/// Get a path relative to a virtual root with a leading separator, the same as `virtualize` does.
+#[inline]
+pub(crate) fn virtualize<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::virtualize(path, virtual_root, cwd)
+}
 
-            if first_is_root && tokens_length > 1 {
-                size -= 1;
-            }
+/// Get the key of an absolutized path which `NormalizedPathKey` compares, i.e., the bytes of the path, which are compared case-sensitively.
+#[inline]
+pub(crate) fn path_key(path: &[u8]) -> Vec<u8> {
+    path.to_vec()
+}
 
-            if has_change || size != self.as_os_str().len() {
-                let mut path_string = OsString::with_capacity(size);
+/// Get the keys of the components of an absolutized path which `PathTrie` compares, i.e., the bytes of the components.
+#[inline]
+pub(crate) fn component_keys(path: &[u8]) -> Vec<Vec<u8>> {
+    engine::components(path).map(|c| c.as_bytes().to_vec()).collect()
+}
 
-                let mut iter = tokens.iter();
+/// Whether `path` starts with `base`, compared component by component.
+#[inline]
+pub(crate) fn starts_with(path: &Path, base: &Path) -> bool {
+    path.starts_with(base)
+}
 
-                path_string.push(iter.next().unwrap());
-
-                if tokens_length > 1 {
-                    if !first_is_root {
-                        path_string.push(MAIN_SEPARATOR.as_os_str());
-                    }
+impl Absolutize for Path {
+    #[inline]
+    fn absolutize(&self) -> io::Result<Cow<'_, Path>> {
+        let cwd = get_cwd!();
 
-                    for token in iter.take(tokens_length - 2) {
-                        path_string.push(token);
-
-                        path_string.push(MAIN_SEPARATOR.as_os_str());
-                    }
+        let path = to_bytes(self.as_os_str());
+        let cwd = to_bytes(cwd.as_os_str());
 
-                    path_string.push(tokens[tokens_length - 1]);
-                }
-
-                let path_buf = PathBuf::from(path_string);
-
-                Ok(Cow::from(path_buf))
-            } else {
-                Ok(Cow::from(self))
-            }
-        } else {
-            Ok(Cow::from(cwd.as_ref().to_owned()))
-        }
+        Ok(from_engine(self, absolutize(&path, &cwd)))
     }
 
-    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<Path>> {
-        let virtual_root = virtual_root.as_ref().absolutize()?;
-
-        let path = self.parse_dot()?;
+    #[inline]
+    fn absolutize_from(&self, cwd: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
+        let path = to_bytes(self.as_os_str());
+        let cwd = to_bytes(cwd.as_ref().as_os_str());
 
-        if path.is_absolute() {
-            if !path.starts_with(&virtual_root) {
-                return Err(io::Error::from(ErrorKind::InvalidInput));
-            }
+        Ok(from_engine(self, engine::absolutize_from(&path, &cwd)))
+    }
 
-            Ok(path)
-        } else {
-            let mut virtual_root = virtual_root.into_owned();
+    #[inline]
+    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
+        let cwd = get_cwd!();
 
-            virtual_root.push(path);
+        let path = to_bytes(self.as_os_str());
+        let virtual_root = to_bytes(virtual_root.as_ref().as_os_str());
+        let cwd = to_bytes(cwd.as_os_str());
 
-            Ok(Cow::from(virtual_root))
-        }
+        Ok(from_engine(self, absolutize_virtually(&path, &virtual_root, &cwd)?))
     }
 }
@@ -0,0 +1,47 @@
+use std::{
+    borrow::Cow,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::windows::{engine, from_engine, system_options, to_bytes};
+
+/// Let `Path` and `PathBuf` be converted to and from the `\\?\` verbatim form, which lets Win32 APIs accept paths longer than `MAX_PATH`.
+pub trait Verbatim {
+    /// Get an absolute path in the verbatim form, i.e., `C:\a` becomes `\\?\C:\a` and `\\server\share\a` becomes `\\?\UNC\server\share\a`.
+    fn to_verbatim(&self) -> io::Result<Cow<'_, Path>>;
+
+    /// Remove the `\\?\` prefix if the path means the same file without it.
+    fn simplify_verbatim(&self) -> Cow<'_, Path>;
+}
+
+impl Verbatim for Path {
+    #[inline]
+    fn to_verbatim(&self) -> io::Result<Cow<'_, Path>> {
+        let cwd = get_cwd!();
+
+        let path = to_bytes(self.as_os_str());
+        let cwd = to_bytes(cwd.as_os_str());
+
+        Ok(from_engine(self, engine::to_verbatim_from_with(&path, &cwd, &system_options(&path))))
+    }
+
+    #[inline]
+    fn simplify_verbatim(&self) -> Cow<'_, Path> {
+        let path = to_bytes(self.as_os_str());
+
+        from_engine(self, engine::simplify_verbatim(&path))
+    }
+}
+
+impl Verbatim for PathBuf {
+    #[inline]
+    fn to_verbatim(&self) -> io::Result<Cow<'_, Path>> {
+        self.as_path().to_verbatim()
+    }
+
+    #[inline]
+    fn simplify_verbatim(&self) -> Cow<'_, Path> {
+        self.as_path().simplify_verbatim()
+    }
+}
@@ -0,0 +1,21 @@
+use std::{borrow::Cow, io, path::Path};
+
+#[cfg(any(unix, all(target_family = "wasm", feature = "use_unix_paths_on_wasm")))]
+use crate::unix as host;
+#[cfg(windows)]
+use crate::windows as host;
+
+/// Get the virtual form of a real path under `virtual_root`, which is the inverse of `absolutize_virtually`, i.e., `/srv/root/a/b` under `/srv/root` becomes `/a/b`. Both paths are absolutized first and compared the same way as `absolutize_virtually` does, so on Windows they are compared ignoring case.
+///
+/// Returns an error of `ErrorKind::InvalidInput` if the path is not under `virtual_root`.
+pub fn virtualize(real_path: &Path, virtual_root: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
+    let cwd = get_cwd!();
+
+    let path = host::to_bytes(real_path.as_os_str());
+    let virtual_root = host::to_bytes(virtual_root.as_ref().as_os_str());
+    let cwd = host::to_bytes(cwd.as_os_str());
+
+    let result = host::virtualize(&path, &virtual_root, &cwd)?;
+
+    Ok(host::from_engine(real_path, result))
+}
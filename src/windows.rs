@@ -1,392 +1,207 @@
 use std::{
     borrow::Cow,
-    ffi::OsString,
-    io::{self, ErrorKind},
-    path::{Component, Path, PathBuf},
+    env,
+    ffi::{OsStr, OsString},
+    io,
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
 };
 
+pub(crate) use crate::engine::windows as engine;
 use crate::{
-    path_dedot::{ParseDot, ParsePrefix, MAIN_SEPARATOR},
-    Absolutize,
+    engine::{
+        explain::{PrefixHandling, Step},
+        policy::PathPolicy,
+        windows::{wtf8, Prefix},
+        Error, Options,
+    },
+    explain::Explained,
+    Absolutize, CwdSource,
 };
 
-impl Absolutize for Path {
-    #[inline]
-    fn absolutize(&self) -> io::Result<Cow<Path>> {
-        let cwd = get_cwd!();
-
-        self.absolutize_from(&cwd)
-    }
-
-    fn absolutize_from(&self, cwd: impl AsRef<Path>) -> io::Result<Cow<Path>> {
-        let mut iter = self.components();
-
-        let mut has_change = false;
-
-        if let Some(first_component) = iter.next() {
-            let mut tokens = Vec::new();
-
-            let (has_prefix, first_is_root) = match first_component {
-                Component::Prefix(prefix) => {
-                    tokens.push(prefix.as_os_str());
-
-                    if let Some(second_component) = iter.next() {
-                        match second_component {
-                            Component::RootDir => {
-                                tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                                (true, true)
-                            },
-                            Component::CurDir => {
-                                // may be unreachable
-
-                                has_change = true;
-
-                                let cwd = cwd.as_ref();
-
-                                for token in cwd.iter().skip(if cwd.get_path_prefix().is_some() {
-                                    1
-                                } else {
-                                    0
-                                }) {
-                                    tokens.push(token);
-                                }
-
-                                (true, tokens.len() > 1 && tokens[1] == MAIN_SEPARATOR.as_os_str())
-                            },
-                            Component::ParentDir => {
-                                has_change = true;
-
-                                let cwd = cwd.as_ref();
-
-                                match cwd.parent() {
-                                    Some(cwd_parent) => {
-                                        for token in cwd_parent.iter().skip(
-                                            if cwd.get_path_prefix().is_some() { 1 } else { 0 },
-                                        ) {
-                                            tokens.push(token);
-                                        }
-
-                                        (
-                                            true,
-                                            tokens.len() > 1
-                                                && tokens[1] == MAIN_SEPARATOR.as_os_str(),
-                                        )
-                                    },
-                                    None => {
-                                        if cwd.get_path_prefix().is_some() {
-                                            if cwd.is_absolute() {
-                                                tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                                                (true, true)
-                                            } else {
-                                                (true, false)
-                                            }
-                                        } else {
-                                            // don't care about `cwd` is "\\" or "\\\"
-                                            if cwd == MAIN_SEPARATOR.as_os_str() {
-                                                tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                                                (true, true)
-                                            } else {
-                                                (true, false)
-                                            }
-                                        }
-                                    },
-                                }
-                            },
-                            _ => {
-                                has_change = true;
-
-                                let out = {
-                                    let cwd = cwd.as_ref();
-
-                                    for token in cwd
-                                        .iter()
-                                        .skip(if cwd.get_path_prefix().is_some() { 1 } else { 0 })
-                                    {
-                                        tokens.push(token);
-                                    }
-
-                                    (
-                                        true,
-                                        tokens.len() > 1 && tokens[1] == MAIN_SEPARATOR.as_os_str(),
-                                    )
-                                };
-
-                                tokens.push(second_component.as_os_str());
-
-                                out
-                            },
-                        }
-                    } else {
-                        tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                        has_change = true;
-
-                        (true, true)
-                    }
-                },
-                Component::RootDir => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
-
-                    match cwd.get_path_prefix() {
-                        Some(prefix) => {
-                            tokens.push(prefix.as_os_str());
-                            tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                            (true, true)
-                        },
-                        None => {
-                            tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                            (false, true)
-                        },
-                    }
-                },
-                Component::CurDir => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
-
-                    for token in cwd.iter() {
-                        tokens.push(token);
-                    }
-
-                    if cwd.get_path_prefix().is_some() {
-                        (true, tokens.len() > 1 && tokens[1] == MAIN_SEPARATOR.as_os_str())
-                    } else {
-                        (false, !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str())
-                    }
-                },
-                Component::ParentDir => {
-                    has_change = true;
-
-                    let cwd = cwd.as_ref();
-
-                    match cwd.parent() {
-                        Some(cwd_parent) => {
-                            for token in cwd_parent.iter() {
-                                tokens.push(token);
-                            }
-
-                            if cwd.get_path_prefix().is_some() {
-                                (true, tokens.len() > 1 && tokens[1] == MAIN_SEPARATOR.as_os_str())
-                            } else {
-                                (
-                                    false,
-                                    !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str(),
-                                )
-                            }
-                        },
-                        None => match cwd.get_path_prefix() {
-                            Some(prefix) => {
-                                tokens.push(prefix.as_os_str());
-
-                                if cwd.is_absolute() {
-                                    tokens.push(MAIN_SEPARATOR.as_os_str());
-
-                                    (true, true)
-                                } else {
-                                    (true, false)
-                                }
-                            },
-                            None => {
-                                // don't care about `cwd` is "\\" or "\\\"
-                                if cwd == MAIN_SEPARATOR.as_os_str() {
-                                    tokens.push(MAIN_SEPARATOR.as_os_str());
+/// Get the WTF-8 bytes which the engine works on.
+#[inline]
+pub(crate) fn to_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    Cow::Owned(wtf8::from_wide(&s.encode_wide().collect::<Vec<u16>>()))
+}
 
-                                    (false, true)
-                                } else {
-                                    (false, false)
-                                }
-                            },
-                        },
-                    }
-                },
-                Component::Normal(token) => {
-                    has_change = true;
+/// Convert WTF-8 bytes returned by the engine back into a path.
+#[inline]
+pub(crate) fn from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(OsString::from_wide(&wtf8::to_wide(&bytes)))
+}
 
-                    let cwd = cwd.as_ref();
+/// Convert a result of the engine into a path. A borrowed result is always the whole input path.
+#[inline]
+pub(crate) fn from_engine<'a>(path: &'a Path, result: Cow<'_, [u8]>) -> Cow<'a, Path> {
+    match result {
+        Cow::Borrowed(_) => Cow::Borrowed(path),
+        Cow::Owned(bytes) => Cow::Owned(from_bytes(bytes)),
+    }
+}
 
-                    for token in cwd.iter() {
-                        tokens.push(token);
-                    }
+/// Get the current directory of the drive of a drive-relative path, like `C:a`, which is kept in the `=C:` environment variable.
+pub(crate) fn drive_cwd(path: &[u8]) -> Option<(u8, Vec<u8>)> {
+    match engine::parse_prefix(path) {
+        Some(Prefix::Disk(drive)) if !engine::has_root(path) => {
+            let drive_cwd = env::var_os(format!("={}:", drive as char))?;
 
-                    let out = if cwd.get_path_prefix().is_some() {
-                        (true, tokens.len() > 1 && tokens[1] == MAIN_SEPARATOR.as_os_str())
-                    } else {
-                        (false, !tokens.is_empty() && tokens[0] == MAIN_SEPARATOR.as_os_str())
-                    };
+            Some((drive, to_bytes(&drive_cwd).into_owned()))
+        },
+        _ => None,
+    }
+}
 
-                    tokens.push(token);
+#[inline]
+fn drive_options(drive_cwd: Option<(u8, Vec<u8>)>) -> Options {
+    match drive_cwd {
+        Some((drive, drive_cwd)) => Options::new().drive_cwd(drive, drive_cwd),
+        None => Options::new(),
+    }
+}
 
-                    out
-                },
-            };
+/// Get the options which let the engine resolve `path` the same way as the system does.
+#[inline]
+pub(crate) fn system_options(path: &[u8]) -> Options {
+    drive_options(drive_cwd(path))
+}
 
-            for component in iter {
-                match component {
-                    Component::CurDir => {
-                        // may be unreachable
-                        has_change = true;
-                    },
-                    Component::ParentDir => {
-                        let tokens_length = tokens.len();
+/// Get an absolute path from the current working directory, the same as `Path::absolutize` does.
+#[inline]
+pub(crate) fn absolutize<'a>(path: &'a [u8], cwd: &[u8]) -> Cow<'a, [u8]> {
+    engine::absolutize_from_with(path, cwd, &system_options(path))
+}
 
-                        if tokens_length > 0
-                            && ((tokens_length != 1 || (!first_is_root && !has_prefix))
-                                && (tokens_length != 2 || !(first_is_root && has_prefix)))
-                        {
-                            tokens.remove(tokens_length - 1);
-                        }
+/// Get an absolute path from the current working directory and the current directory of the drive of `path`, which `drive_cwd` returns.
+#[inline]
+pub(crate) fn absolutize_with_drive_cwd<'a>(
+    path: &'a [u8],
+    cwd: &[u8],
+    drive_cwd: Option<(u8, Vec<u8>)>,
+) -> Cow<'a, [u8]> {
+    engine::absolutize_from_with(path, cwd, &drive_options(drive_cwd))
+}
 
-                        has_change = true;
-                    },
-                    _ => {
-                        tokens.push(component.as_os_str());
+/// Get an absolute path from `cwd` and the steps taken to get it, the same as `AbsolutizeExplained` does. If `use_drive_cwds` is `true`, a drive-relative path is resolved from the current directory of its drive the same way as `absolutize` does.
+///
+/// Also returns the current working directory which the path is actually resolved from and its source if it is not `cwd`, i.e., if the path is relative to another drive.
+pub(crate) fn explain<'a>(path: &'a [u8], cwd: &[u8], use_drive_cwds: bool) -> Explained<'a> {
+    let drive_cwd = if use_drive_cwds { drive_cwd(path) } else { None };
+
+    let options = drive_options(drive_cwd.clone());
+
+    let (result, steps) = engine::explain_from_with(path, cwd, &options);
+
+    let resolved_from = steps.iter().find_map(|step| match step {
+        Step::Prefix {
+            prefix,
+            handling: PrefixHandling::ResolvedFromCwd {
+                cwd: used, ..
+            },
+        } if used.as_slice() != cwd => match engine::parse_prefix(prefix) {
+            Some(Prefix::Disk(drive)) => {
+                let drive = drive.to_ascii_uppercase() as char;
+
+                match &drive_cwd {
+                    Some((_, drive_cwd)) if drive_cwd == used => {
+                        Some((used.clone(), CwdSource::DriveVariable {
+                            drive,
+                        }))
                     },
+                    _ => Some((format!("{}:\\", drive).into_bytes(), CwdSource::DriveRoot {
+                        drive,
+                    })),
                 }
-            }
-
-            let tokens_length = tokens.len();
-
-            debug_assert!(tokens_length > 0);
-
-            let mut size = tokens.iter().fold(tokens_length - 1, |acc, &x| acc + x.len());
-
-            if has_prefix {
-                if tokens_length > 1 {
-                    size -= 1;
-
-                    if first_is_root {
-                        if tokens_length > 2 {
-                            size -= 1;
-                        }
-                    }
-                }
-            } else if first_is_root && tokens_length > 1 {
-                size -= 1;
-            }
-
-            if has_change || size != self.as_os_str().len() {
-                let mut path_string = OsString::with_capacity(size);
-
-                let mut iter = tokens.iter();
+            },
+            _ => None,
+        },
+        _ => None,
+    });
 
-                path_string.push(iter.next().unwrap());
-
-                if tokens_length > 1 {
-                    if has_prefix {
-                        if let Some(token) = iter.next() {
-                            path_string.push(token);
+    (result, steps, resolved_from)
+}
 
-                            if tokens_length > 2 {
-                                if !first_is_root {
-                                    path_string.push(MAIN_SEPARATOR.as_os_str());
-                                }
+/// Get an absolute path under a virtual root, the same as `Path::absolutize_virtually` does.
+#[inline]
+pub(crate) fn absolutize_virtually<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::absolutize_virtually_with(path, virtual_root, cwd, &system_options(path))
+}
 
-                                for token in iter.take(tokens_length - 3) {
-                                    path_string.push(token);
+/// Get an absolute path under a virtual root, checking the names of the path with a policy first.
+#[inline]
+pub(crate) fn absolutize_virtually_with_policy<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+    policy: &PathPolicy,
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::absolutize_virtually_with(
+        path,
+        virtual_root,
+        cwd,
+        &system_options(path).path_policy(policy.clone()),
+    )
+}
 
-                                    path_string.push(MAIN_SEPARATOR.as_os_str());
-                                }
+/// Get a path relative to a virtual root with a leading separator, the same as `virtualize` does.
+#[inline]
+pub(crate) fn virtualize<'a>(
+    path: &'a [u8],
+    virtual_root: &[u8],
+    cwd: &[u8],
+) -> Result<Cow<'a, [u8]>, Error> {
+    engine::virtualize_with(path, virtual_root, cwd, &system_options(path))
+}
 
-                                path_string.push(tokens[tokens_length - 1]);
-                            }
-                        }
-                    } else {
-                        if !first_is_root {
-                            path_string.push(MAIN_SEPARATOR.as_os_str());
-                        }
+/// Get the key of an absolutized path which `NormalizedPathKey` compares, i.e., the path with its case folded, because Windows compares paths ignoring case.
+#[inline]
+pub(crate) fn path_key(path: &[u8]) -> Vec<u8> {
+    engine::case_fold_key(path)
+}
 
-                        for token in iter.take(tokens_length - 2) {
-                            path_string.push(token);
+/// Get the keys of the components of an absolutized path which `PathTrie` compares, i.e., the components with their case folded.
+#[inline]
+pub(crate) fn component_keys(path: &[u8]) -> Vec<Vec<u8>> {
+    engine::components(path).map(|c| engine::case_fold_key(c.as_bytes())).collect()
+}
 
-                            path_string.push(MAIN_SEPARATOR.as_os_str());
-                        }
+/// Whether `path` starts with `base`, compared component by component and ignoring case like the file system does.
+#[inline]
+pub(crate) fn starts_with(path: &Path, base: &Path) -> bool {
+    engine::starts_with_ignore_case(&to_bytes(path.as_os_str()), &to_bytes(base.as_os_str()))
+}
 
-                        path_string.push(tokens[tokens_length - 1]);
-                    }
-                }
+impl Absolutize for Path {
+    #[inline]
+    fn absolutize(&self) -> io::Result<Cow<'_, Path>> {
+        let cwd = get_cwd!();
 
-                let path_buf = PathBuf::from(path_string);
+        let path = to_bytes(self.as_os_str());
+        let cwd = to_bytes(cwd.as_os_str());
 
-                Ok(Cow::from(path_buf))
-            } else {
-                Ok(Cow::from(self))
-            }
-        } else {
-            Ok(Cow::from(cwd.as_ref().to_owned()))
-        }
+        Ok(from_engine(self, absolutize(&path, &cwd)))
     }
 
-    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<Path>> {
-        let virtual_root = virtual_root.as_ref().absolutize()?;
-
-        let path = self.parse_dot()?;
-
-        if path.is_absolute() {
-            let path_lowercase = path
-                .to_str()
-                .ok_or_else(|| io::Error::new(ErrorKind::Other, "The path is not valid UTF-8."))?
-                .to_lowercase();
-
-            let virtual_root_lowercase = virtual_root
-                .to_str()
-                .ok_or_else(|| {
-                    io::Error::new(ErrorKind::Other, "The virtual root is not valid UTF-8.")
-                })?
-                .to_lowercase();
-
-            if !&path_lowercase.starts_with(&virtual_root_lowercase) {
-                return Err(io::Error::from(ErrorKind::InvalidInput));
-            }
-
-            Ok(path)
-        } else if let Some(prefix) = path.get_path_prefix() {
-            let prefix = prefix.as_os_str().to_str().ok_or_else(|| {
-                io::Error::new(ErrorKind::Other, "The prefix of the path is not valid UTF-8.")
-            })?;
-
-            let prefix_lowercase = prefix.to_lowercase();
-
-            let virtual_root_prefix_lowercase = virtual_root
-                .get_path_prefix()
-                .unwrap()
-                .as_os_str()
-                .to_str()
-                .ok_or_else(|| {
-                    io::Error::new(
-                        ErrorKind::Other,
-                        "The prefix of the virtual root is not valid UTF-8.",
-                    )
-                })?
-                .to_lowercase();
-
-            if prefix_lowercase == virtual_root_prefix_lowercase {
-                let path = path.to_str().ok_or_else(|| {
-                    io::Error::new(ErrorKind::Other, "The path is not valid UTF-8.")
-                })?;
-
-                let path_without_prefix = Path::new(&path[prefix.len()..]);
-
-                let mut virtual_root = virtual_root.into_owned();
+    #[inline]
+    fn absolutize_from(&self, cwd: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
+        let path = to_bytes(self.as_os_str());
+        let cwd = to_bytes(cwd.as_ref().as_os_str());
 
-                virtual_root.push(path_without_prefix);
+        Ok(from_engine(self, engine::absolutize_from(&path, &cwd)))
+    }
 
-                Ok(Cow::from(virtual_root))
-            } else {
-                Err(io::Error::from(ErrorKind::InvalidInput))
-            }
-        } else {
-            let mut virtual_root = virtual_root.into_owned();
+    #[inline]
+    fn absolutize_virtually(&self, virtual_root: impl AsRef<Path>) -> io::Result<Cow<'_, Path>> {
+        let cwd = get_cwd!();
 
-            virtual_root.push(path);
+        let path = to_bytes(self.as_os_str());
+        let virtual_root = to_bytes(virtual_root.as_ref().as_os_str());
+        let cwd = to_bytes(cwd.as_os_str());
 
-            Ok(Cow::from(virtual_root))
-        }
+        Ok(from_engine(self, absolutize_virtually(&path, &virtual_root, &cwd)?))
     }
 }
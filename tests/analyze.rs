@@ -0,0 +1,36 @@
+#![cfg(feature = "std")]
+
+use std::path::Path;
+
+use path_absolutize::{
+    analyze, analyze_with,
+    engine::analyze::{AnalysisRules, AnomalyKind},
+};
+
+#[test]
+fn analyze_path() {
+    assert!(analyze(Path::new("src/lib.rs")).is_clean());
+
+    let report = analyze(Path::new("./src//lib.rs"));
+
+    assert_eq!(
+        vec![AnomalyKind::CurDir, AnomalyKind::RedundantSeparator],
+        report.anomalies().iter().map(|a| a.kind).collect::<Vec<_>>()
+    );
+    assert_eq!(6..7, report.anomalies()[1].span);
+}
+
+#[test]
+fn analyze_with_rules() {
+    let rules = AnalysisRules::new().expect_relative(true);
+
+    #[cfg(unix)]
+    let path = Path::new("/etc/passwd");
+    #[cfg(windows)]
+    let path = Path::new(r"C:\Windows");
+
+    assert!(analyze(path).is_clean());
+    assert!(analyze_with(path, &rules).contains(AnomalyKind::NotRelative));
+
+    assert!(analyze_with(Path::new("../outside"), &rules).contains(AnomalyKind::ParentDirEscape));
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::{engine::archive::ArchivePolicy, sanitize_archive_entry};
+
+#[cfg(unix)]
+#[test]
+fn sanitize_archive_entry_unix() {
+    let sanitize =
+        |name: &str| sanitize_archive_entry(name, "/tmp/dest", ArchivePolicy::Clamp).unwrap();
+
+    assert_eq!(Path::new("/tmp/dest/a/b.txt"), sanitize("a/b.txt"));
+    assert_eq!(Path::new("/tmp/dest/etc/x"), sanitize("../../etc/x"));
+    assert_eq!(Path::new("/tmp/dest/b"), sanitize(r"a\..\..\b"));
+    assert_eq!(Path::new("/tmp/dest/x"), sanitize(r"C:\x"));
+    assert_eq!(Path::new("/tmp/dest/x"), sanitize(r"\\server\share\x"));
+    assert_eq!(Path::new("/tmp/dest/abs"), sanitize("/abs"));
+    assert_eq!(Path::new("/tmp/dest"), sanitize("./"));
+    assert_eq!(Path::new("/tmp/dest/logs/12:30.txt"), sanitize("logs/12:30.txt"));
+
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        sanitize_archive_entry("x/../C:/y", "/tmp/dest", ArchivePolicy::Clamp).unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        sanitize_archive_entry("../../etc/x", "/tmp/dest", ArchivePolicy::Reject)
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn sanitize_archive_entry_windows() {
+    let sanitize = |name: &str| sanitize_archive_entry(name, r"C:\dest", ArchivePolicy::Clamp);
+
+    assert_eq!(Path::new(r"C:\dest\a\b.txt"), sanitize("a/b.txt").unwrap());
+    assert_eq!(Path::new(r"C:\dest\etc\x"), sanitize("../../etc/x").unwrap());
+    assert_eq!(Path::new(r"C:\dest\x"), sanitize(r"D:\x").unwrap());
+    assert_eq!(Path::new(r"C:\dest\x"), sanitize(r"\\server\share\x").unwrap());
+    assert_eq!(ErrorKind::InvalidInput, sanitize("x/../C:/y").unwrap_err().kind());
+}
@@ -0,0 +1,43 @@
+#![cfg(all(unix, feature = "camino", not(feature = "unsafe_cache")))]
+
+use std::{borrow::Cow, env, io::ErrorKind};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use path_absolutize::Utf8Absolutize;
+
+#[test]
+fn absolutize() {
+    let p = Utf8Path::new("./path/to/123/../456");
+
+    let cwd = Utf8PathBuf::from_path_buf(env::current_dir().unwrap()).unwrap();
+
+    assert_eq!(cwd.join("path/to/456"), p.absolutize().unwrap());
+}
+
+#[test]
+fn absolutize_borrowed() {
+    let p = Utf8Path::new("/path/to/123/456");
+
+    assert!(matches!(p.absolutize().unwrap(), Cow::Borrowed(_)));
+}
+
+#[test]
+fn absolutize_from() {
+    let p = Utf8PathBuf::from("../path/to/123/456");
+
+    assert_eq!("/cwd/path/to/123/456", p.absolutize_from("/cwd/dir").unwrap().as_str());
+}
+
+#[test]
+fn absolutize_virtually() {
+    let p = Utf8Path::new("path/to/../../../../123/456");
+
+    assert_eq!("/virtual/root/123/456", p.absolutize_virtually("/virtual/root").unwrap().as_str());
+
+    let p = Utf8Path::new("/path/to/123/456");
+
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        p.absolutize_virtually("/virtual/root").unwrap_err().kind()
+    );
+}
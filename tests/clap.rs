@@ -0,0 +1,74 @@
+#![cfg(all(unix, feature = "clap", not(feature = "unsafe_cache")))]
+
+use std::{env, path::PathBuf};
+
+use clap::{error::ErrorKind, Arg, Command};
+use path_absolutize::clap::AbsolutizeValueParser;
+
+fn command(parser: AbsolutizeValueParser) -> Command {
+    Command::new("app").arg(Arg::new("path").long("path").value_parser(parser))
+}
+
+fn parse(parser: AbsolutizeValueParser, value: &str) -> Result<PathBuf, clap::Error> {
+    command(parser)
+        .try_get_matches_from(["app", "--path", value])
+        .map(|matches| matches.get_one::<PathBuf>("path").unwrap().clone())
+}
+
+#[test]
+fn cwd() {
+    assert_eq!(
+        env::current_dir().unwrap().join("path/to/456"),
+        parse(AbsolutizeValueParser::new(), "./path/to/123/../456").unwrap()
+    );
+
+    assert_eq!(
+        PathBuf::from("/path/to/456"),
+        parse(AbsolutizeValueParser::new(), "/path/to/123/../456").unwrap()
+    );
+}
+
+#[test]
+fn empty() {
+    let err = parse(AbsolutizeValueParser::new(), "").unwrap_err();
+
+    assert_eq!(ErrorKind::ValueValidation, err.kind());
+}
+
+#[test]
+fn virtually() {
+    assert_eq!(
+        PathBuf::from("/virtual/root/123/456"),
+        parse(AbsolutizeValueParser::virtually("/virtual/root"), "path/to/../../../../123/456")
+            .unwrap()
+    );
+
+    assert_eq!(
+        PathBuf::from("/virtual/root/123"),
+        parse(AbsolutizeValueParser::virtually("/virtual/root"), "/virtual/root/123").unwrap()
+    );
+
+    let err = parse(AbsolutizeValueParser::virtually("/virtual/root"), "/etc/passwd").unwrap_err();
+
+    assert_eq!(ErrorKind::ValueValidation, err.kind());
+    assert!(err.to_string().contains("virtual root"));
+}
+
+#[test]
+fn within() {
+    assert_eq!(
+        PathBuf::from("/root/dir/123/456"),
+        parse(AbsolutizeValueParser::within("/root/dir"), "path/../123/./456").unwrap()
+    );
+
+    assert_eq!(
+        PathBuf::from("/root/dir/456"),
+        parse(AbsolutizeValueParser::within("/root/dir"), "/root/dir/123/../456").unwrap()
+    );
+
+    for value in ["..", "path/../../123", "/root/dir2", "/root/dir/.."] {
+        let err = parse(AbsolutizeValueParser::within("/root/dir"), value).unwrap_err();
+
+        assert_eq!(ErrorKind::ValueValidation, err.kind(), "{}", value);
+    }
+}
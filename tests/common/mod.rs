@@ -0,0 +1,4 @@
+/// Convert the bytes of a path returned by the engine into a `String`, so that the tests can compare it with string literals.
+pub fn utf8(bytes: impl Into<Vec<u8>>) -> String {
+    String::from_utf8(bytes.into()).unwrap()
+}
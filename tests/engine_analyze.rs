@@ -0,0 +1,120 @@
+use path_absolutize::engine::{
+    analyze::{AnalysisRules, AnomalyKind, PathReport},
+    posix, windows, Options,
+};
+
+fn anomalies(report: PathReport) -> Vec<(AnomalyKind, usize, usize)> {
+    report.anomalies().iter().map(|a| (a.kind, a.span.start, a.span.end)).collect()
+}
+
+#[test]
+fn analyze_posix() {
+    let rules = AnalysisRules::new();
+
+    assert!(posix::analyze(b"", &rules).is_clean());
+    assert!(posix::analyze(b"a/b/c", &rules).is_clean());
+    assert!(posix::analyze(b"/", &rules).is_clean());
+    assert!(posix::analyze(b"/a/b", &rules).is_clean());
+    assert!(posix::analyze(b"a/../b", &rules).is_clean());
+
+    assert_eq!(
+        vec![
+            (AnomalyKind::CurDir, 0, 1),
+            (AnomalyKind::RedundantSeparator, 4, 6),
+            (AnomalyKind::CurDir, 6, 7),
+            (AnomalyKind::TrailingSeparator, 9, 11),
+        ],
+        anomalies(posix::analyze(b"./a///./b//", &rules))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::ParentDirEscape, 0, 2), (AnomalyKind::ParentDirEscape, 8, 10)],
+        anomalies(posix::analyze(b"../a/../../b", &rules))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::ParentDirEscape, 1, 3)],
+        anomalies(posix::analyze(b"/..", &rules))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::RedundantSeparator, 1, 2)],
+        anomalies(posix::analyze(b"//a", &rules))
+    );
+}
+
+#[test]
+fn analyze_posix_double_slash_root() {
+    let rules = AnalysisRules::new();
+    let options = Options::new().posix_double_slash_root(true);
+
+    assert!(posix::analyze_with(b"//", &rules, &options).is_clean());
+    assert!(posix::analyze_with(b"//host/a", &rules, &options).is_clean());
+
+    assert_eq!(
+        vec![(AnomalyKind::RedundantSeparator, 1, 3)],
+        anomalies(posix::analyze_with(b"///a", &rules, &options))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::CurDir, 2, 3), (AnomalyKind::TrailingSeparator, 8, 9)],
+        anomalies(posix::analyze_with(b"//./host/", &rules, &options))
+    );
+}
+
+#[test]
+fn analyze_posix_expect_relative() {
+    let rules = AnalysisRules::new().expect_relative(true);
+
+    assert!(posix::analyze(b"a/b", &rules).is_clean());
+
+    assert_eq!(vec![(AnomalyKind::NotRelative, 0, 1)], anomalies(posix::analyze(b"/a", &rules)));
+    assert_eq!(
+        vec![(AnomalyKind::NotRelative, 0, 2), (AnomalyKind::RedundantSeparator, 1, 2)],
+        anomalies(posix::analyze(b"//a", &rules))
+    );
+}
+
+#[test]
+fn analyze_windows() {
+    let rules = AnalysisRules::new();
+
+    assert!(windows::analyze(br"C:\a\b", &rules).is_clean());
+    assert!(windows::analyze(b"C:/a/b", &rules).is_clean());
+    assert!(windows::analyze(br"\\server\share\a", &rules).is_clean());
+
+    assert_eq!(
+        vec![(AnomalyKind::MixedSeparators, 4, 5), (AnomalyKind::TrailingSeparator, 6, 8)],
+        anomalies(windows::analyze(br"C:\a/b\\", &rules))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::CurDir, 15, 16)],
+        anomalies(windows::analyze(br"\\server\share\.", &rules))
+    );
+    assert_eq!(
+        vec![(AnomalyKind::ParentDirEscape, 2, 4)],
+        anomalies(windows::analyze(br"C:..\a", &rules))
+    );
+
+    // verbatim paths are literal
+    assert!(windows::analyze(br"\\?\C:\a\.\..\\", &rules).is_clean());
+}
+
+#[test]
+fn analyze_windows_expect_relative() {
+    let rules = AnalysisRules::new().expect_relative(true);
+
+    assert!(windows::analyze(br"a\b", &rules).is_clean());
+
+    assert_eq!(vec![(AnomalyKind::NotRelative, 0, 2)], anomalies(windows::analyze(b"C:a", &rules)));
+    assert_eq!(vec![(AnomalyKind::NotRelative, 0, 1)], anomalies(windows::analyze(br"\a", &rules)));
+    assert_eq!(
+        vec![(AnomalyKind::NotRelative, 0, 7)],
+        anomalies(windows::analyze(br"\\?\C:\a\.\..\\", &rules))
+    );
+}
+
+#[test]
+fn display() {
+    let report = posix::analyze(b"a//b/", &AnalysisRules::new());
+
+    assert!(report.contains(AnomalyKind::TrailingSeparator));
+    assert!(!report.contains(AnomalyKind::CurDir));
+    assert_eq!("redundant separator at 2..3\ntrailing separator at 4..5", report.to_string());
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::utf8;
+use path_absolutize::engine::{
+    archive::{sanitize_entry_name, ArchivePolicy},
+    windows::Win32IssueKind,
+    Error,
+};
+
+fn clamp(name: &str) -> Result<String, Error> {
+    sanitize_entry_name(name.as_bytes(), ArchivePolicy::Clamp).map(utf8)
+}
+
+fn reject(name: &str) -> Result<String, Error> {
+    sanitize_entry_name(name.as_bytes(), ArchivePolicy::Reject).map(utf8)
+}
+
+const fn colon(index: usize) -> Result<&'static str, Error> {
+    Err(Error::Win32Name {
+        kind: Win32IssueKind::AlternateDataStream,
+        index,
+    })
+}
+
+/// Known malicious entry names, and what they become with `ArchivePolicy::Clamp`.
+const MALICIOUS: &[(&str, Result<&str, Error>)] = &[
+    ("../../etc/x", Ok("etc/x")),
+    ("../../../../../../../../etc/passwd", Ok("etc/passwd")),
+    ("..", Ok("")),
+    ("../", Ok("")),
+    ("./../x", Ok("x")),
+    ("a/../../x", Ok("x")),
+    ("a/b/../../../x", Ok("x")),
+    ("/abs", Ok("abs")),
+    ("//abs", Ok("abs")),
+    ("/../etc/passwd", Ok("etc/passwd")),
+    (r"a\..\..\b", Ok("b")),
+    (r"..\..\Windows\System32\x.dll", Ok("Windows/System32/x.dll")),
+    (r"..\/..\/x", Ok("x")),
+    (r"C:\x", Ok("x")),
+    ("C:/x", Ok("x")),
+    ("C:x", Ok("x")),
+    (r"c:\..\x", Ok("x")),
+    (r"C:..\x", Ok("x")),
+    (r"\\server\share\x", Ok("x")),
+    ("//server/share/x", Ok("x")),
+    (r"\\server\share\..\..\x", Ok("x")),
+    (r"\\?\C:\x", Ok("x")),
+    (r"\\?\C:\..\x", Ok("x")),
+    (r"\\?\UNC\server\share\x", Ok("x")),
+    (r"\\?\GLOBALROOT\Device\x", Ok("Device/x")),
+    (r"\\.\COM1\x", Ok("x")),
+    ("//?/C:/x", Ok("x")),
+    (r"\x", Ok("x")),
+    (r"\..\x", Ok("x")),
+    ("x/../C:/evil", colon(2)),
+    (r"C:x\..\D:y", colon(2)),
+    ("a/../b/../c:", colon(4)),
+    ("a/C:/b", Ok("a/C:/b")),
+    (r"\\?\C:\a\D:", Ok("a/D:")),
+];
+
+#[test]
+fn malicious_clamp() {
+    for &(name, expected) in MALICIOUS {
+        assert_eq!(expected.map(String::from), clamp(name), "{:?}", name);
+    }
+}
+
+#[test]
+fn malicious_reject() {
+    for &(name, expected) in MALICIOUS {
+        match reject(name) {
+            Ok(sanitized) => assert_eq!(expected, Ok(sanitized.as_str()), "{:?}", name),
+            Err(err) if expected.is_err() => assert_eq!(expected, Err(err), "{:?}", name),
+            Err(err) => {
+                assert_eq!(Error::OutsideVirtualRoot, err, "{:?}", name);
+                assert!(name.contains(".."), "{:?}", name);
+            },
+        }
+    }
+
+    assert_eq!(Err(Error::OutsideVirtualRoot), reject("../../etc/x"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), reject(r"a\..\..\b"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), reject(r"\\server\share\..\x"));
+    assert_eq!(Ok("x".to_string()), reject(r"C:\x"));
+    assert_eq!(Ok("abs".to_string()), reject("/abs"));
+}
+
+#[test]
+fn benign() {
+    for policy in [ArchivePolicy::Clamp, ArchivePolicy::Reject] {
+        let sanitize = |name: &str| utf8(sanitize_entry_name(name.as_bytes(), policy).unwrap());
+
+        assert_eq!("a/b/c.txt", sanitize("a/b/c.txt"));
+        assert_eq!("a/b", sanitize("a/b/"));
+        assert_eq!("a/c.txt", sanitize("a/b/../c.txt"));
+        assert_eq!("a/b", sanitize(r"a\b"));
+        assert_eq!("a/b", sanitize("./a//./b/"));
+        assert_eq!("", sanitize("./"));
+        assert_eq!("", sanitize(""));
+        assert_eq!("...", sanitize("..."));
+        assert_eq!("..a/b..", sanitize("..a/b.."));
+        assert_eq!("é/ü", sanitize("é/ü"));
+        assert_eq!("12:30.txt", sanitize("12:30.txt"));
+        assert_eq!("logs/12:30.txt", sanitize("logs/12:30.txt"));
+        assert_eq!("a/b:c", sanitize("a/b:c"));
+    }
+}
+
+#[test]
+fn non_utf8() {
+    assert_eq!(
+        b"\xFF/\xFE".as_ref(),
+        sanitize_entry_name(b"../\xFF/./\xFE", ArchivePolicy::Clamp).unwrap().as_slice()
+    );
+}
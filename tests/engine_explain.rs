@@ -0,0 +1,193 @@
+use path_absolutize::engine::{
+    explain::{PrefixHandling, Step},
+    posix, windows, Options, SeparatorStyle,
+};
+
+fn kept(name: &str) -> Step {
+    Step::Kept {
+        name: name.as_bytes().to_vec()
+    }
+}
+
+fn removed(name: &str) -> Step {
+    Step::ParentDirRemoved {
+        name: name.as_bytes().to_vec()
+    }
+}
+
+#[test]
+fn explain_posix() {
+    let (path, steps) = posix::explain_from_with(b"a/../../b/./c", b"/cwd", &Options::new());
+
+    assert_eq!(b"/b/c".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![
+            Step::ResolvedFromCwd {
+                cwd: b"/cwd".to_vec(), parent: false
+            },
+            kept("a"),
+            removed("a"),
+            removed("cwd"),
+            kept("b"),
+            kept("c"),
+        ],
+        steps
+    );
+
+    let (path, steps) = posix::explain_from_with(b"/a/../..", b"/cwd", &Options::new());
+
+    assert_eq!(b"/".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![
+            Step::Root {
+                root: b"/".to_vec()
+            },
+            kept("a"),
+            removed("a"),
+            Step::ParentDirClamped,
+        ],
+        steps
+    );
+
+    let (path, steps) = posix::explain_from_with(b"", b"/cwd", &Options::new());
+
+    assert_eq!(b"/cwd".as_ref(), path.as_ref());
+    assert_eq!(vec![Step::Empty], steps);
+}
+
+#[test]
+fn explain_windows() {
+    let (path, steps) = windows::explain_from_with(br"\a\..\b", br"C:\cwd", &Options::new());
+
+    assert_eq!(br"C:\b".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![
+            Step::Prefix {
+                prefix: b"C:".to_vec(), handling: PrefixHandling::TakenFromCwd
+            },
+            Step::Root {
+                root: br"\".to_vec()
+            },
+            kept("a"),
+            removed("a"),
+            kept("b"),
+        ],
+        steps
+    );
+
+    let options = Options::new().drive_cwd(b'D', br"D:\work");
+
+    let (path, steps) = windows::explain_from_with(br"D:..\x", br"C:\cwd", &options);
+
+    assert_eq!(br"D:\x".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![
+            Step::Prefix {
+                prefix:   b"D:".to_vec(),
+                handling: PrefixHandling::ResolvedFromCwd {
+                    cwd:    br"D:\work".to_vec(),
+                    parent: true,
+                },
+            },
+            kept("x"),
+        ],
+        steps
+    );
+
+    let (path, steps) = windows::explain_from_with(br"\\?\C:\a\..", br"C:\cwd", &Options::new());
+
+    assert_eq!(br"\\?\C:\a\..".as_ref(), path.as_ref());
+    assert_eq!(vec![Step::Literal], steps);
+
+    let (path, steps) = windows::explain_from_with(b"C:", br"C:\cwd", &Options::new());
+
+    assert_eq!(br"C:\".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![Step::Prefix {
+            prefix: b"C:".to_vec(), handling: PrefixHandling::RootAdded
+        }],
+        steps
+    );
+
+    let options = Options::new().separator_style(SeparatorStyle::ForwardSlash);
+
+    let (path, steps) = windows::explain_from_with(br"\\server\share", br"C:\cwd", &options);
+
+    assert_eq!(b"//server/share/".as_ref(), path.as_ref());
+    assert_eq!(
+        vec![
+            Step::Prefix {
+                prefix: br"\\server\share".to_vec(), handling: PrefixHandling::Kept
+            },
+            Step::SeparatorsRewritten {
+                style: SeparatorStyle::ForwardSlash
+            },
+        ],
+        steps
+    );
+}
+
+#[test]
+fn explain_agrees_with_absolutize() {
+    let paths: &[&[u8]] =
+        &[b"", b".", b"..", b"./a", b"../a/./b", b"/a/../../b", b"a/b/../../..", b"//a//b/.."];
+
+    for path in paths {
+        for cwd in [b"/".as_ref(), b"/a/b", b"rel", b""] {
+            let options = Options::new();
+
+            assert_eq!(
+                posix::absolutize_from_with(path, cwd, &options),
+                posix::explain_from_with(path, cwd, &options).0
+            );
+        }
+    }
+
+    let paths: &[&[u8]] = &[
+        b"",
+        b"..",
+        br"C:",
+        br"C:a\..\..",
+        br"D:..",
+        br"\a\..",
+        br"\\server\share\..",
+        br"\\?\C:\.",
+        br"\\.\C:",
+        b"a./b. /..",
+    ];
+
+    for path in paths {
+        for cwd in [br"C:\".as_ref(), br"C:\a\b", br"\\server\share\a", b"rel"] {
+            for options in [
+                Options::new(),
+                Options::new().win32_normalization(true),
+                Options::new().separator_style(SeparatorStyle::Backslash),
+            ] {
+                assert_eq!(
+                    windows::absolutize_from_with(path, cwd, &options),
+                    windows::explain_from_with(path, cwd, &options).0
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!("kept `a`", kept("a").to_string());
+    assert_eq!("`..` removed `a`", removed("a").to_string());
+    assert_eq!(
+        "resolved from the parent of the current working directory `/cwd`",
+        Step::ResolvedFromCwd {
+            cwd: b"/cwd".to_vec(), parent: true
+        }
+        .to_string()
+    );
+    assert_eq!(
+        r"took the prefix `C:` from the current working directory",
+        Step::Prefix {
+            prefix: b"C:".to_vec(), handling: PrefixHandling::TakenFromCwd
+        }
+        .to_string()
+    );
+}
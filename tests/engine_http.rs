@@ -0,0 +1,163 @@
+mod common;
+
+use common::utf8;
+use path_absolutize::engine::{
+    http::{decode_request_path, RequestPathIssue, RequestPolicy, TrailingSlash},
+    Error,
+};
+
+fn decode(url_path: &str) -> Result<String, Error> {
+    decode_with(url_path, &RequestPolicy::new())
+}
+
+fn decode_with(url_path: &str, policy: &RequestPolicy) -> Result<String, Error> {
+    decode_request_path(url_path.as_bytes(), policy).map(utf8)
+}
+
+#[test]
+fn decode_request_path_lv0() {
+    assert_eq!(Ok("index.html".to_string()), decode("/index.html"));
+    assert_eq!(Ok("a/b/c.txt".to_string()), decode("/a/b/c.txt"));
+    assert_eq!(Ok("a/c.txt".to_string()), decode("/a/./b/../c.txt"));
+    assert_eq!(Ok("a/b".to_string()), decode("//a///b"));
+    assert_eq!(Ok("a/b".to_string()), decode("a/b"));
+    assert_eq!(Ok("".to_string()), decode("/"));
+    assert_eq!(Ok("".to_string()), decode(""));
+    assert_eq!(Ok("".to_string()), decode("/a/.."));
+}
+
+#[test]
+fn decode_request_path_percent() {
+    assert_eq!(Ok("hello world.txt".to_string()), decode("/hello%20world.txt"));
+    assert_eq!(Ok("日本".to_string()), decode("/%E6%97%A5%e6%9c%ac"));
+    assert_eq!(Ok("a+b".to_string()), decode("/a+b"));
+    assert_eq!(Ok("100%".to_string()), decode("/100%25"));
+
+    let invalid = Err(Error::RequestPath(RequestPathIssue::InvalidPercentEncoding));
+
+    assert_eq!(invalid, decode("/%"));
+    assert_eq!(invalid, decode("/%4"));
+    assert_eq!(invalid, decode("/%zz"));
+    assert_eq!(invalid, decode("/%%32%65"));
+
+    assert_eq!(
+        b"\xFF".as_ref(),
+        decode_request_path(b"/%FF", &RequestPolicy::new()).unwrap().as_slice()
+    );
+}
+
+/// Known malicious request paths.
+#[test]
+fn decode_request_path_malicious() {
+    let outside = Err(Error::OutsideVirtualRoot);
+
+    assert_eq!(outside, decode("/.."));
+    assert_eq!(outside, decode("/../etc/passwd"));
+    assert_eq!(outside, decode("/a/../../etc/passwd"));
+    assert_eq!(outside, decode("/%2e%2e/etc/passwd"));
+    assert_eq!(outside, decode("/%2E%2E/%2E%2E/etc/passwd"));
+    assert_eq!(outside, decode("/.%2e/etc/passwd"));
+
+    let nul = Err(Error::RequestPath(RequestPathIssue::Nul));
+
+    assert_eq!(nul, decode("/a%00.html"));
+    assert_eq!(nul, decode("/a\0.html"));
+
+    let encoded_slash = Err(Error::RequestPath(RequestPathIssue::EncodedSlash));
+
+    assert_eq!(encoded_slash, decode("/..%2Fetc%2Fpasswd"));
+    assert_eq!(encoded_slash, decode("/a%2fb"));
+
+    let backslash = Err(Error::RequestPath(RequestPathIssue::Backslash));
+
+    assert_eq!(backslash, decode(r"/..\..\windows\win.ini"));
+    assert_eq!(backslash, decode("/..%5C..%5Cwindows%5Cwin.ini"));
+    assert_eq!(backslash, decode("/a%5cb"));
+
+    let dotfile = Err(Error::RequestPath(RequestPathIssue::Dotfile));
+
+    assert_eq!(dotfile, decode("/.git/config"));
+    assert_eq!(dotfile, decode("/.env"));
+    assert_eq!(dotfile, decode("/%2Egit/config"));
+    assert_eq!(dotfile, decode("/a/.htaccess"));
+    assert_eq!(dotfile, decode("/..."));
+}
+
+#[test]
+fn decode_request_path_policies() {
+    let policy = RequestPolicy::new().encoded_slashes(true);
+
+    assert_eq!(Ok("a/b".to_string()), decode_with("/a%2Fb", &policy));
+    assert_eq!(Err(Error::OutsideVirtualRoot), decode_with("/..%2Fetc", &policy));
+
+    let policy = RequestPolicy::new().backslashes(true);
+
+    assert_eq!(Ok("a/b/c".to_string()), decode_with(r"/a\b%5Cc", &policy));
+    assert_eq!(Err(Error::OutsideVirtualRoot), decode_with(r"/..\windows", &policy));
+    assert_eq!(
+        Err(Error::RequestPath(RequestPathIssue::EncodedSlash)),
+        decode_with("/a%2Fb", &policy)
+    );
+
+    let policy = RequestPolicy::new().dotfiles(true);
+
+    assert_eq!(Ok(".well-known/x".to_string()), decode_with("/.well-known/x", &policy));
+    assert_eq!(Ok("...".to_string()), decode_with("/...", &policy));
+    assert_eq!(Err(Error::OutsideVirtualRoot), decode_with("/..", &policy));
+}
+
+#[test]
+fn decode_request_path_trailing_slash() {
+    assert_eq!(Ok("docs".to_string()), decode("/docs/"));
+
+    let policy = RequestPolicy::new().trailing_slash(TrailingSlash::Keep);
+
+    assert_eq!(Ok("docs/".to_string()), decode_with("/docs/", &policy));
+    assert_eq!(Ok("docs".to_string()), decode_with("/docs", &policy));
+    assert_eq!(Ok("".to_string()), decode_with("/", &policy));
+
+    let policy = RequestPolicy::new().trailing_slash(TrailingSlash::Reject);
+
+    assert_eq!(
+        Err(Error::RequestPath(RequestPathIssue::TrailingSlash)),
+        decode_with("/docs/", &policy)
+    );
+    assert_eq!(Ok("".to_string()), decode_with("/", &policy));
+}
+
+/// Whatever the input is, an accepted path stays under the document root.
+#[test]
+fn decode_request_path_fuzz() {
+    const ALPHABET: &[u8] = b"/\\.%2eEfF5cC0a";
+
+    let policies = [
+        RequestPolicy::new(),
+        RequestPolicy::new().encoded_slashes(true).backslashes(true).dotfiles(true),
+    ];
+
+    let mut state: u32 = 0x2545_F491;
+
+    for _ in 0..20000 {
+        let mut input = Vec::new();
+
+        for _ in 0..(state % 16) {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+
+            input.push(ALPHABET[state as usize % ALPHABET.len()]);
+        }
+
+        for policy in policies.iter() {
+            if let Ok(decoded) = decode_request_path(&input, policy) {
+                assert!(!decoded.starts_with(b"/"), "{:?}", input);
+                assert!(!decoded.contains(&b'\\'), "{:?}", input);
+                assert!(
+                    decoded.split(|&b| b == b'/').all(|name| name != b".." && name != b"."),
+                    "{:?}",
+                    input
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use path_absolutize::engine::{
+    policy::{PathPolicy, PolicyViolation, PolicyViolationKind},
+    posix, windows, Error, Options,
+};
+
+fn posix_violations(path: &str, policy: &PathPolicy) -> Vec<(usize, PolicyViolationKind)> {
+    posix::policy_violations(path.as_bytes(), policy)
+        .into_iter()
+        .map(|violation| (violation.index, violation.kind))
+        .collect()
+}
+
+fn windows_violations(path: &str, policy: &PathPolicy) -> Vec<(usize, PolicyViolationKind)> {
+    windows::policy_violations(path.as_bytes(), policy)
+        .into_iter()
+        .map(|violation| (violation.index, violation.kind))
+        .collect()
+}
+
+#[test]
+fn no_rules() {
+    let policy = PathPolicy::new();
+
+    assert!(posix_violations("/a/../.git/\0\n/CON:", &policy).is_empty());
+    assert!(windows_violations(r"C:\a\..\.git\CON.", &policy).is_empty());
+}
+
+#[test]
+fn each_rule() {
+    use PolicyViolationKind::*;
+
+    assert_eq!(vec![(2, Nul)], posix_violations("/a/b\0c/d", &PathPolicy::new().reject_nul(true)));
+    assert_eq!(
+        vec![(0, ControlCharacter), (1, ControlCharacter)],
+        posix_violations("a\n/\x7Fb/c\0", &PathPolicy::new().reject_control_characters(true))
+    );
+    assert_eq!(
+        vec![(2, ComponentTooLong)],
+        posix_violations("/abc/abcd/", &PathPolicy::new().max_component_length(3))
+    );
+    assert_eq!(
+        vec![(3, TooManyComponents), (4, TooManyComponents)],
+        posix_violations("/a/../b/c", &PathPolicy::new().max_components(2))
+    );
+    assert_eq!(
+        vec![(1, HiddenComponent), (3, HiddenComponent), (4, HiddenComponent)],
+        posix_violations("./.git/../.env/...", &PathPolicy::new().reject_hidden_components(true))
+    );
+    assert_eq!(
+        vec![(2, ParentDir)],
+        posix_violations("/a/../b", &PathPolicy::new().reject_parent_dirs(true))
+    );
+    assert_eq!(
+        vec![
+            (1, WindowsInvalidCharacter),
+            (2, WindowsInvalidCharacter),
+            (3, WindowsReservedName),
+            (4, WindowsTrailingDotOrSpace),
+            (5, WindowsTrailingDotOrSpace),
+        ],
+        posix_violations("/a:b/a\\b/nul.txt/a./a ", &PathPolicy::new().windows_compatible(true))
+    );
+    assert_eq!(
+        vec![(1, NonPortableCharacter), (2, NonPortableCharacter)],
+        posix_violations("/a b/-a/a-b_c.D9", &PathPolicy::new().portable_characters(true))
+    );
+}
+
+#[test]
+fn every_violation() {
+    let violations = posix::policy_violations(b"/ok/.\x01bad:/x", &PathPolicy::web_upload());
+
+    assert_eq!(
+        vec![
+            PolicyViolation {
+                kind:  PolicyViolationKind::ControlCharacter,
+                index: 2,
+                name:  b".\x01bad:",
+            },
+            PolicyViolation {
+                kind:  PolicyViolationKind::HiddenComponent,
+                index: 2,
+                name:  b".\x01bad:",
+            },
+            PolicyViolation {
+                kind:  PolicyViolationKind::WindowsInvalidCharacter,
+                index: 2,
+                name:  b".\x01bad:",
+            },
+        ],
+        violations
+    );
+}
+
+#[test]
+fn windows_components() {
+    use PolicyViolationKind::*;
+
+    let policy = PathPolicy::portable_filename();
+
+    // the prefix and the root directory are not names
+    assert!(windows_violations(r"\\server\share\a\b.txt", &policy).is_empty());
+    assert!(windows_violations(r"C:\a/b.txt", &policy).is_empty());
+
+    assert_eq!(vec![(2, WindowsReservedName)], windows_violations(r"C:\com1.log", &policy));
+    assert_eq!(
+        vec![(3, WindowsInvalidCharacter), (3, NonPortableCharacter)],
+        windows_violations(r"C:\a\b:c", &policy)
+    );
+}
+
+#[test]
+fn presets() {
+    use PolicyViolationKind::*;
+
+    assert!(posix_violations("/usr/lib/libc.so.6", &PathPolicy::strict_posix()).is_empty());
+    assert_eq!(
+        vec![(1, NonPortableCharacter)],
+        posix_violations("/é", &PathPolicy::strict_posix())
+    );
+    assert!(posix_violations("/x/CON", &PathPolicy::strict_posix()).is_empty());
+    assert_eq!(
+        vec![(2, WindowsReservedName)],
+        posix_violations("/x/CON", &PathPolicy::portable_filename())
+    );
+
+    assert!(posix_violations("uploads/2024/photo 1.jpg", &PathPolicy::web_upload()).is_empty());
+    assert_eq!(vec![(1, ParentDir)], posix_violations("a/../b", &PathPolicy::web_upload()));
+    assert_eq!(
+        vec![(32, TooManyComponents)],
+        posix_violations(&"a/".repeat(33), &PathPolicy::web_upload())
+    );
+}
+
+#[test]
+fn combine() {
+    let policy = PathPolicy::new()
+        .max_component_length(10)
+        .combine(&PathPolicy::new().max_component_length(3).reject_hidden_components(true));
+
+    assert_eq!(PathPolicy::new().max_component_length(3).reject_hidden_components(true), policy);
+
+    assert_eq!(
+        PathPolicy::portable_filename(),
+        PathPolicy::strict_posix().combine(&PathPolicy::new().windows_compatible(true))
+    );
+}
+
+#[test]
+fn absolutize_virtually_with_policy() {
+    let options = Options::new().path_policy(PathPolicy::web_upload());
+
+    assert_eq!(
+        b"/srv/root/a/b".as_ref(),
+        posix::absolutize_virtually_with(b"a/b", b"/srv/root", b"/cwd", &options).unwrap().as_ref()
+    );
+    assert_eq!(
+        Err(Error::PolicyViolation {
+            kind: PolicyViolationKind::HiddenComponent, index: 1
+        }),
+        posix::absolutize_virtually_with(b"a/.env", b"/srv/root", b"/cwd", &options)
+    );
+    assert_eq!(
+        Err(Error::PolicyViolation {
+            kind: PolicyViolationKind::ParentDir, index: 1
+        }),
+        posix::absolutize_virtually_with(b"a/../b", b"/srv/root", b"/cwd", &options)
+    );
+    assert_eq!(
+        Err(Error::PolicyViolation {
+            kind: PolicyViolationKind::WindowsReservedName, index: 1
+        }),
+        windows::absolutize_virtually_with(br"a\aux", br"C:\root", br"C:\cwd", &options)
+    );
+
+    // names which `..` removes are checked too, and only the first violation is returned
+    let options = Options::new().path_policy(PathPolicy::new().reject_hidden_components(true));
+
+    assert_eq!(
+        Err(Error::PolicyViolation {
+            kind: PolicyViolationKind::HiddenComponent, index: 0
+        }),
+        posix::absolutize_virtually_with(b".env/.git/../../b", b"/srv/root", b"/cwd", &options)
+    );
+    assert_eq!(
+        Err(Error::PolicyViolation {
+            kind: PolicyViolationKind::HiddenComponent, index: 1
+        }),
+        windows::absolutize_virtually_with(br"a\.git\..\b", br"C:\root", br"C:\cwd", &options)
+    );
+}
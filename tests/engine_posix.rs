@@ -0,0 +1,316 @@
+mod common;
+
+use std::borrow::Cow;
+
+use common::utf8;
+use path_absolutize::engine::{posix, Error, Options};
+
+fn absolutize_from(path: &str, cwd: &str) -> String {
+    utf8(posix::absolutize_from(path.as_bytes(), cwd.as_bytes()))
+}
+
+fn absolutize_virtually(path: &str, virtual_root: &str, cwd: &str) -> Result<String, Error> {
+    posix::absolutize_virtually(path.as_bytes(), virtual_root.as_bytes(), cwd.as_bytes()).map(utf8)
+}
+
+fn absolutize_from_double_slash(path: &str, cwd: &str) -> String {
+    let options = Options::new().posix_double_slash_root(true);
+
+    utf8(posix::absolutize_from_with(path.as_bytes(), cwd.as_bytes(), &options))
+}
+
+fn absolutize_virtually_double_slash(
+    path: &str,
+    virtual_root: &str,
+    cwd: &str,
+) -> Result<String, Error> {
+    let options = Options::new().posix_double_slash_root(true);
+
+    posix::absolutize_virtually_with(
+        path.as_bytes(),
+        virtual_root.as_bytes(),
+        cwd.as_bytes(),
+        &options,
+    )
+    .map(utf8)
+}
+
+#[test]
+fn absolutize_from_lv0() {
+    assert_eq!("/path/to/123/456", absolutize_from("/path/to/123/456", "/cwd"));
+    assert_eq!("/path/to/456", absolutize_from("/path/to/./123/../456", "/cwd"));
+    assert_eq!("/cwd/path/to/456", absolutize_from("./path/to/123/../456", "/cwd"));
+    assert_eq!("/path/to/456", absolutize_from("../path/to/123/../456", "/cwd"));
+    assert_eq!("/cwd/path/to/456", absolutize_from("path/to/123/../456", "/cwd"));
+}
+
+#[test]
+fn absolutize_from_lv1() {
+    assert_eq!("/", absolutize_from("/", "/foo/bar/baz"));
+    assert_eq!("/foo/bar/baz", absolutize_from("", "/foo/bar/baz"));
+    assert_eq!("foo/bar/baz", absolutize_from("", "foo/bar/baz"));
+    assert_eq!("/abc", absolutize_from("./abc", "/"));
+    assert_eq!("abc", absolutize_from("./abc", ""));
+    assert_eq!("/abc", absolutize_from("../abc", "/"));
+    assert_eq!("abc", absolutize_from("../abc", ""));
+    assert_eq!("foo/bar/abc", absolutize_from("../abc", "foo/bar/baz"));
+    assert_eq!("/", absolutize_from("/../..", "/cwd"));
+}
+
+#[test]
+fn absolutize_from_borrowed() {
+    assert!(matches!(posix::absolutize_from(b"/path/to", b"/cwd"), Cow::Borrowed(_)));
+    assert!(matches!(posix::absolutize_from(b"/path/to/", b"/cwd"), Cow::Owned(_)));
+}
+
+#[test]
+fn absolutize_from_non_utf8() {
+    assert_eq!(
+        b"/cwd/\xFF/\xFE".as_ref(),
+        posix::absolutize_from(b"\xFF/./\xFE", b"/cwd").as_ref()
+    );
+}
+
+#[test]
+fn absolutize_virtually_lv0() {
+    assert_eq!(
+        Ok("/path/to/456".to_string()),
+        absolutize_virtually("/path/to/./123/../456", "/", "/cwd")
+    );
+    assert_eq!(
+        Ok("/path/to/123/456".to_string()),
+        absolutize_virtually("/path/to/123/456", "/path", "/cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually("/path/to/123/456", "/pat", "/cwd")
+    );
+}
+
+#[test]
+fn absolutize_virtually_lv1() {
+    assert_eq!(
+        Ok("/virtual/root/path/to/456".to_string()),
+        absolutize_virtually("path/to/123/../456", "/virtual/root", "/cwd")
+    );
+    assert_eq!(Ok("/cwd/root/path".to_string()), absolutize_virtually("path", "root", "/cwd"));
+    assert_eq!(Ok("/cwd/path".to_string()), absolutize_virtually("./path", "/cwd", "/cwd"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), absolutize_virtually("../path", "/cwd", "/cwd"));
+}
+
+#[test]
+fn absolutize_virtually_empty() {
+    assert_eq!(Ok("/srv/root".to_string()), absolutize_virtually("a/..", "/srv/root", "/cwd"));
+    assert_eq!(Ok("/srv/root".to_string()), absolutize_virtually(".", "/srv/root", "/srv/root"));
+    assert_eq!(Ok("/".to_string()), absolutize_virtually("a/..", "/", "/cwd"));
+}
+
+#[test]
+fn absolutize_virtually_parent_dir() {
+    // `..` and `./..` are resolved from the current working directory
+    assert_eq!(Err(Error::OutsideVirtualRoot), absolutize_virtually("..", "/srv/root", "/cwd"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), absolutize_virtually("./..", "/srv/root", "/cwd"));
+    assert_eq!(Ok("/srv/root".to_string()), absolutize_virtually("a/../..", "/srv/root", "/cwd"));
+}
+
+#[test]
+fn double_slash_collapsed_by_default() {
+    assert_eq!("/host/share", absolutize_from("//host/share", "/cwd"));
+    assert_eq!("/host/x", absolutize_from("x", "//host"));
+    assert_eq!("/", absolutize_from("..", "//"));
+}
+
+#[test]
+fn double_slash_root() {
+    assert_eq!("//host/share", absolutize_from_double_slash("//host/share", "/cwd"));
+    assert_eq!("//host/share", absolutize_from_double_slash("//host//share/.", "/cwd"));
+    assert_eq!("//", absolutize_from_double_slash("//", "/cwd"));
+    assert_eq!("/host/share", absolutize_from_double_slash("///host/share", "/cwd"));
+    assert_eq!("/host", absolutize_from_double_slash("////host", "/cwd"));
+    assert_eq!("/", absolutize_from_double_slash("///", "/cwd"));
+    assert_eq!("/host", absolutize_from_double_slash("/host", "//cwd"));
+
+    assert!(matches!(
+        posix::absolutize_from_with(
+            b"//host/share",
+            b"/cwd",
+            &Options::new().posix_double_slash_root(true)
+        ),
+        Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn double_slash_root_parent_dir() {
+    assert_eq!("//host", absolutize_from_double_slash("//host/share/..", "/cwd"));
+    assert_eq!("//", absolutize_from_double_slash("//host/..", "/cwd"));
+    assert_eq!("//", absolutize_from_double_slash("//host/../..", "/cwd"));
+    assert_eq!("//x", absolutize_from_double_slash("//../../x", "/cwd"));
+    assert_eq!("/", absolutize_from_double_slash("///..", "/cwd"));
+}
+
+#[test]
+fn double_slash_root_cwd() {
+    assert_eq!("//host/share/x", absolutize_from_double_slash("x", "//host/share"));
+    assert_eq!("//host/share/x", absolutize_from_double_slash("./x", "//host/share"));
+    assert_eq!("//host/x", absolutize_from_double_slash("../x", "//host/share"));
+    assert_eq!("//x", absolutize_from_double_slash("../x", "//host"));
+    assert_eq!("//", absolutize_from_double_slash("..", "//"));
+    assert_eq!("/host/x", absolutize_from_double_slash("x", "///host"));
+}
+
+#[test]
+fn double_slash_root_absolutize_virtually() {
+    assert_eq!(
+        Ok("//host/share/a/b".to_string()),
+        absolutize_virtually_double_slash("a/./b", "//host/share", "/cwd")
+    );
+    assert_eq!(
+        Ok("//host/share/b".to_string()),
+        absolutize_virtually_double_slash("//host/share/a/../b", "//host/share", "/cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually_double_slash("/host/share/a", "//host/share", "/cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually_double_slash("//host/share/a", "/host/share", "/cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually_double_slash("//host/other", "//host/share", "/cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually_double_slash("//host/share/../other", "//host/share", "/cwd")
+    );
+    assert_eq!(
+        Ok("//host/share/a".to_string()),
+        absolutize_virtually_double_slash("./a", "//host/share", "//host/share")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually_double_slash("../a", "//host/share", "//host/share")
+    );
+
+    // without the option, `//host` is `/host`
+    assert_eq!(
+        Ok("/host/share/a".to_string()),
+        absolutize_virtually("//host/share/a", "/host/share", "/cwd")
+    );
+}
+
+#[test]
+fn virtualize() {
+    let virtualize = |path: &str, virtual_root: &str| {
+        posix::virtualize(path.as_bytes(), virtual_root.as_bytes(), b"/cwd").map(utf8)
+    };
+
+    assert_eq!(Ok("/a/b".to_string()), virtualize("/srv/root/a/b", "/srv/root"));
+    assert_eq!(Ok("/a/b".to_string()), virtualize("/srv/root/a/./x/../b/", "/srv/root/"));
+    assert_eq!(Ok("/".to_string()), virtualize("/srv/root", "/srv/root"));
+    assert_eq!(Ok("/srv/a".to_string()), virtualize("/srv/a", "/"));
+    assert_eq!(Ok("/a".to_string()), virtualize("root/a", "root"));
+
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize("/srv/root2/a", "/srv/root"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize("/srv/root/../a", "/srv/root"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize("/Srv/root/a", "/srv/root"));
+
+    assert!(matches!(posix::virtualize(b"/srv/a", b"/", b"/cwd"), Ok(Cow::Borrowed(_))));
+
+    let options = Options::new().posix_double_slash_root(true);
+
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        posix::virtualize_with(b"/host/a", b"//host", b"/cwd", &options)
+    );
+    assert_eq!(
+        b"/a".as_ref(),
+        posix::virtualize_with(b"//host/a", b"//host", b"/cwd", &options).unwrap().as_ref()
+    );
+
+    // the inverse of `absolutize_virtually`
+    for path in ["a/b", "a"] {
+        let real = absolutize_virtually(path, "/srv/root", "/cwd").unwrap();
+
+        assert_eq!(Ok(format!("/{}", path)), virtualize(&real, "/srv/root"));
+    }
+}
+
+#[test]
+fn limits() {
+    use path_absolutize::engine::{Limit, Limits};
+
+    fn try_absolutize(path: &[u8], limits: Limits) -> Result<Cow<'_, [u8]>, Error> {
+        posix::try_absolutize_from_with(path, b"/cwd", &Options::new().limits(limits))
+    }
+
+    assert_eq!(b"/cwd/a/b".as_ref(), try_absolutize(b"a/./b", Limits::new()).unwrap().as_ref());
+    assert_eq!(
+        b"/cwd/a/b".as_ref(),
+        try_absolutize(
+            b"a/./b",
+            Limits::new()
+                .max_input_length(5)
+                .max_output_length(8)
+                .max_components(3)
+                .max_parent_dirs(0)
+        )
+        .unwrap()
+        .as_ref()
+    );
+
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::InputLength)),
+        try_absolutize(b"a/./b", Limits::new().max_input_length(4))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::OutputLength)),
+        try_absolutize(b"a/./b", Limits::new().max_output_length(7))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::Components)),
+        try_absolutize(b"/a/b/c", Limits::new().max_components(3))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::ParentDirs)),
+        try_absolutize(b"a/../b/../c/..", Limits::new().max_parent_dirs(2))
+    );
+
+    // adversarial inputs
+    let long = b"a/../".repeat(200_000);
+
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::InputLength)),
+        try_absolutize(&long, Limits::new().max_input_length(4096))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::ParentDirs)),
+        try_absolutize(&long, Limits::new().max_parent_dirs(64))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::Components)),
+        try_absolutize(&long, Limits::new().max_components(256))
+    );
+
+    let options = Options::new().limits(Limits::new().max_parent_dirs(1).max_output_length(12));
+
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::ParentDirs)),
+        posix::absolutize_virtually_with(b"a/../../b", b"/root", b"/cwd", &options)
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::OutputLength)),
+        posix::absolutize_virtually_with(b"abc/def/ghi", b"/root", b"/cwd", &options)
+    );
+    assert_eq!(
+        b"/root/abc".as_ref(),
+        posix::absolutize_virtually_with(b"abc", b"/root", b"/cwd", &options).unwrap().as_ref()
+    );
+    // only the `try_*` functions and `absolutize_virtually_with` check the limits
+    assert_eq!(
+        b"/cwd/b".as_ref(),
+        posix::absolutize_from_with(b"a/../../b", b"/cwd/x", &options).as_ref()
+    );
+}
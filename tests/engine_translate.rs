@@ -0,0 +1,108 @@
+mod common;
+
+use common::utf8;
+use path_absolutize::engine::translate::{self, DriveMount};
+
+fn posix_to_windows(path: &str, cwd: &str, mount: &DriveMount) -> Option<String> {
+    translate::posix_to_windows(path.as_bytes(), cwd.as_bytes(), mount).map(utf8)
+}
+
+fn windows_to_posix(path: &str, cwd: &str, mount: &DriveMount) -> Option<String> {
+    translate::windows_to_posix(path.as_bytes(), cwd.as_bytes(), mount).map(utf8)
+}
+
+#[test]
+fn drive_mount() {
+    assert_eq!(b"/", DriveMount::msys2().as_bytes());
+    assert_eq!(b"/cygdrive/", DriveMount::cygwin().as_bytes());
+    assert_eq!(b"/mnt/", DriveMount::wsl().as_bytes());
+
+    assert_eq!(b"/", DriveMount::new("").as_bytes());
+    assert_eq!(b"/mnt/", DriveMount::new("mnt").as_bytes());
+    assert_eq!(b"/mnt/host/", DriveMount::new("//mnt//host").as_bytes());
+}
+
+#[test]
+fn posix_to_windows_msys2() {
+    let mount = DriveMount::msys2();
+
+    assert_eq!(Some(r"C:\Users\x"), posix_to_windows("/c/Users/x", "/", &mount).as_deref());
+    assert_eq!(Some(r"D:\Users\x"), posix_to_windows("/D/Users/x", "/", &mount).as_deref());
+    assert_eq!(Some(r"C:\"), posix_to_windows("/c", "/", &mount).as_deref());
+    assert_eq!(Some(r"C:\"), posix_to_windows("/c/", "/", &mount).as_deref());
+    assert_eq!(Some(r"C:\x"), posix_to_windows("/c/Users/../x", "/", &mount).as_deref());
+    assert_eq!(Some(r"C:\Users\x"), posix_to_windows("x", "/c/Users", &mount).as_deref());
+    assert_eq!(Some(r"C:\x"), posix_to_windows("../x", "/c/Users", &mount).as_deref());
+
+    assert_eq!(None, posix_to_windows("/", "/", &mount));
+    assert_eq!(None, posix_to_windows("/usr/bin", "/", &mount));
+    assert_eq!(None, posix_to_windows("/1/x", "/", &mount));
+    assert_eq!(None, posix_to_windows("x", "/home/user", &mount));
+    assert_eq!(None, posix_to_windows(r"/c/a\b", "/", &mount));
+}
+
+#[test]
+fn posix_to_windows_cygwin_and_wsl() {
+    let cygwin = DriveMount::cygwin();
+
+    assert_eq!(Some(r"C:\x"), posix_to_windows("/cygdrive/c/x", "/", &cygwin).as_deref());
+    assert_eq!(Some(r"C:\"), posix_to_windows("/cygdrive/c", "/", &cygwin).as_deref());
+    assert_eq!(None, posix_to_windows("/cygdrive", "/", &cygwin));
+    assert_eq!(None, posix_to_windows("/cygdrivec/x", "/", &cygwin));
+    assert_eq!(None, posix_to_windows("/c/x", "/", &cygwin));
+
+    let wsl = DriveMount::wsl();
+
+    assert_eq!(Some(r"C:\Users\x"), posix_to_windows("/mnt/c/Users/x", "/", &wsl).as_deref());
+    assert_eq!(Some(r"E:\x"), posix_to_windows("./x", "/mnt/e", &wsl).as_deref());
+    assert_eq!(None, posix_to_windows("/mnt/cd/x", "/", &wsl));
+    assert_eq!(None, posix_to_windows("/mnt/c/../wsl/x", "/", &wsl));
+
+    let custom = DriveMount::new("/drives");
+
+    assert_eq!(Some(r"C:\x"), posix_to_windows("/drives/c/x", "/", &custom).as_deref());
+    assert_eq!(None, posix_to_windows("/mnt/c/x", "/", &custom));
+}
+
+#[test]
+fn windows_to_posix_mounts() {
+    let msys2 = DriveMount::msys2();
+    let cygwin = DriveMount::cygwin();
+    let wsl = DriveMount::wsl();
+
+    assert_eq!(Some("/c/Users/x"), windows_to_posix(r"C:\Users\x", r"C:\", &msys2).as_deref());
+    assert_eq!(
+        Some("/cygdrive/c/Users/x"),
+        windows_to_posix(r"C:\Users\x", r"C:\", &cygwin).as_deref()
+    );
+    assert_eq!(Some("/mnt/c/Users/x"), windows_to_posix(r"C:\Users\x", r"C:\", &wsl).as_deref());
+
+    assert_eq!(Some("/d/x"), windows_to_posix(r"d:/x", r"C:\", &msys2).as_deref());
+    assert_eq!(Some("/c"), windows_to_posix(r"C:\", r"C:\", &msys2).as_deref());
+    assert_eq!(Some("/mnt/c"), windows_to_posix(r"C:\", r"C:\", &wsl).as_deref());
+    assert_eq!(Some("/c/x"), windows_to_posix(r"C:\Users\..\x", r"C:\", &msys2).as_deref());
+    assert_eq!(Some("/c/Users/x"), windows_to_posix(r"x", r"C:\Users", &msys2).as_deref());
+    assert_eq!(Some("/c/x"), windows_to_posix(r"\x", r"C:\Users", &msys2).as_deref());
+    assert_eq!(Some("/c/Users/x"), windows_to_posix(r"\\?\C:\Users\x", r"C:\", &msys2).as_deref());
+}
+
+#[test]
+fn windows_to_posix_not_on_a_drive() {
+    let msys2 = DriveMount::msys2();
+
+    assert_eq!(None, windows_to_posix(r"\\server\share\x", r"C:\", &msys2));
+    assert_eq!(None, windows_to_posix(r"\\.\COM1", r"C:\", &msys2));
+    assert_eq!(None, windows_to_posix(r"\\?\C:\a\..\x", r"C:\", &msys2));
+    assert_eq!(None, windows_to_posix(r"\x", r"\\server\share", &msys2));
+}
+
+#[test]
+fn round_trip() {
+    for mount in [DriveMount::msys2(), DriveMount::cygwin(), DriveMount::wsl()] {
+        for path in [r"C:\", r"C:\Users\x", r"Z:\a b\c.txt"] {
+            let posix = windows_to_posix(path, r"C:\", &mount).unwrap();
+
+            assert_eq!(Some(path), posix_to_windows(&posix, "/", &mount).as_deref());
+        }
+    }
+}
@@ -0,0 +1,529 @@
+mod common;
+
+use std::borrow::Cow;
+
+use common::utf8;
+use path_absolutize::engine::{
+    windows::{self, wtf8, DriveCwds, Prefix},
+    Error, Options,
+};
+
+fn absolutize_from(path: &str, cwd: &str) -> String {
+    utf8(windows::absolutize_from(path.as_bytes(), cwd.as_bytes()))
+}
+
+fn absolutize_virtually(path: &str, virtual_root: &str, cwd: &str) -> Result<String, Error> {
+    windows::absolutize_virtually(path.as_bytes(), virtual_root.as_bytes(), cwd.as_bytes())
+        .map(utf8)
+}
+
+#[test]
+fn parse_prefix() {
+    assert_eq!(Some(Prefix::Disk(b'C')), windows::parse_prefix(b"c:"));
+    assert_eq!(Some(Prefix::Disk(b'C')), windows::parse_prefix(br"C:\foo"));
+    assert_eq!(
+        Some(Prefix::UNC(b"server", b"share")),
+        windows::parse_prefix(br"\\server\share\foo")
+    );
+    assert_eq!(Some(Prefix::UNC(b"server", b"share")), windows::parse_prefix(b"//server/share"));
+    assert_eq!(None, windows::parse_prefix(br"\\server"));
+    assert_eq!(Some(Prefix::VerbatimDisk(b'C')), windows::parse_prefix(br"\\?\C:\foo"));
+    assert_eq!(Some(Prefix::Verbatim(b"C:foo")), windows::parse_prefix(br"\\?\C:foo"));
+    assert_eq!(
+        Some(Prefix::VerbatimUNC(b"server", b"share")),
+        windows::parse_prefix(br"\\?\UNC\server\share")
+    );
+    assert_eq!(Some(Prefix::DeviceNS(b"COM1")), windows::parse_prefix(br"\\.\COM1"));
+    assert_eq!(Some(Prefix::UNC(b"?", b"C:")), windows::parse_prefix(b"//?/C:/foo"));
+}
+
+#[test]
+fn absolutize_from_lv0() {
+    assert_eq!(r"C:\path\to\123\456", absolutize_from(r"C:\path\to\123\456", r"C:\cwd"));
+    assert_eq!(r"C:\path\to\456", absolutize_from(r"C:\path\to\.\123\..\456", r"C:\cwd"));
+    assert_eq!(r"C:\cwd\path\to\456", absolutize_from(r".\path\to\123\..\456", r"C:\cwd"));
+    assert_eq!(r"C:\path\to\456", absolutize_from(r"..\path\to\123\..\456", r"C:\cwd"));
+    assert_eq!(r"C:\cwd\path\to\456", absolutize_from(r"path/to/123/../456", r"C:\cwd"));
+    assert_eq!(r"D:\path", absolutize_from(r"\path", r"D:\cwd"));
+}
+
+#[test]
+fn absolutize_from_lv1() {
+    assert_eq!(r"C:\", absolutize_from(r"C:\", r"\foo\bar\baz"));
+    assert_eq!(r"C:\", absolutize_from(r"C:", r"foo\bar\baz"));
+    assert_eq!(r"\foo\bar\baz", absolutize_from("", r"\foo\bar\baz"));
+    assert_eq!(r"foo\bar\baz\abc", absolutize_from("abc", r"foo\bar\baz"));
+    assert_eq!(r"\abc", absolutize_from(r".\abc", r"\"));
+    assert_eq!("abc", absolutize_from(r".\abc", ""));
+    assert_eq!("C:abc", absolutize_from(r".\abc", "C:"));
+    assert_eq!(r"C:\abc", absolutize_from(r"..\abc", r"C:\"));
+    assert_eq!(r"C:foo\bar\abc", absolutize_from(r"..\abc", r"C:foo\bar\baz"));
+}
+
+#[test]
+fn absolutize_from_lv2() {
+    assert_eq!(r"C:\abc", absolutize_from(r"C:.\abc", r"\"));
+    assert_eq!("C:abc", absolutize_from(r"C:..\abc", ""));
+    assert_eq!(r"C:\foo\bar\baz\abc", absolutize_from(r"C:.\abc", r"\foo\bar\baz"));
+    assert_eq!(r"C:foo\bar\baz\abc", absolutize_from(r"C:.\abc", r"C:foo\bar\baz"));
+    assert_eq!(r"C:\foo\bar\abc", absolutize_from(r"C:..\abc", r"C:\foo\bar\baz"));
+    assert_eq!(r"C:foo\bar\abc", absolutize_from(r"C:..\abc", r"foo\bar\baz"));
+}
+
+#[test]
+fn absolutize_from_unc() {
+    assert_eq!(r"\\VBOXSRV\test\", absolutize_from(r"\\VBOXSRV\test", r"C:\cwd"));
+    assert_eq!(r"\\VBOXSRV\test\", absolutize_from(r"\\VBOXSRV\test\", r"C:\cwd"));
+    assert_eq!(r"\\VBOXSRV\test\b", absolutize_from(r"\\VBOXSRV\test\a\..\b", r"C:\cwd"));
+}
+
+#[test]
+fn absolutize_from_borrowed() {
+    assert!(matches!(windows::absolutize_from(br"C:\path\to", br"C:\cwd"), Cow::Borrowed(_)));
+    assert!(matches!(windows::absolutize_from(br"C:\path\to\", br"C:\cwd"), Cow::Owned(_)));
+}
+
+#[test]
+fn absolutize_from_unpaired_surrogate() {
+    let path = wtf8::from_wide(&[b'a' as u16, 0xD800]);
+
+    let result = windows::absolutize_from(&path, br"C:\cwd");
+
+    assert_eq!(
+        [
+            b'C' as u16,
+            b':' as u16,
+            b'\\' as u16,
+            b'c' as u16,
+            b'w' as u16,
+            b'd' as u16,
+            b'\\' as u16,
+            b'a' as u16,
+            0xD800
+        ]
+        .as_ref(),
+        wtf8::to_wide(&result).as_slice()
+    );
+}
+
+#[test]
+fn absolutize_virtually_lv0() {
+    assert_eq!(Ok(r"C:\123\456".to_string()), absolutize_virtually(r"123\456\", r"C:\", r"C:\cwd"));
+    assert_eq!(
+        Ok(r"C:\123\456".to_string()),
+        absolutize_virtually(r"C:123\456\", r"C:\", r"C:\cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually(r"C:123\456\", r"D:\", r"C:\cwd")
+    );
+}
+
+#[test]
+fn absolutize_virtually_lv1() {
+    assert_eq!(
+        Ok(r"c:\Root\a".to_string()),
+        absolutize_virtually(r"c:\Root\a", r"C:\root", r"C:\cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually(r"C:\other\a", r"C:\root", r"C:\cwd")
+    );
+    assert_eq!(Ok(r"C:\cwd\root\a".to_string()), absolutize_virtually("a", "root", r"C:\cwd"));
+}
+
+#[test]
+fn absolutize_virtually_empty() {
+    assert_eq!(Ok(r"C:\root".to_string()), absolutize_virtually(r"a\..", r"C:\root", r"C:\cwd"));
+    assert_eq!(Ok(r"C:\root".to_string()), absolutize_virtually(r"C:a\..", r"C:\root", r"C:\cwd"));
+    assert_eq!(Ok(r"C:\".to_string()), absolutize_virtually(r"a\..", r"C:\", r"C:\cwd"));
+}
+
+#[test]
+fn absolutize_virtually_parent_dir() {
+    // `..` and `.\..` are resolved from the current working directory
+    assert_eq!(Err(Error::OutsideVirtualRoot), absolutize_virtually("..", r"C:\root", r"C:\cwd"));
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually(r".\..", r"C:\root", r"C:\cwd")
+    );
+    assert_eq!(Ok(r"C:\root".to_string()), absolutize_virtually(r"a\..\..", r"C:\root", r"C:\cwd"));
+}
+
+fn absolutize_from_with(path: &str, cwd: &str, options: &Options) -> String {
+    utf8(windows::absolutize_from_with(path.as_bytes(), cwd.as_bytes(), options))
+}
+
+#[test]
+fn drive_cwds_other_drive() {
+    let options = Options::new();
+
+    assert_eq!(r"D:\foo", absolutize_from_with("D:foo", r"C:\cwd", &options));
+    assert_eq!(r"D:\foo", absolutize_from_with(r"D:..\foo", r"C:\cwd", &options));
+    assert_eq!(r"d:\foo", absolutize_from_with("d:foo", r"\\server\share\cwd", &options));
+
+    let options = options.drive_cwd(b'd', r"D:\d\cwd");
+
+    assert_eq!(r"D:\d\cwd\foo", absolutize_from_with("D:foo", r"C:\cwd", &options));
+    assert_eq!(r"d:\d\foo", absolutize_from_with(r"d:..\foo", r"C:\cwd", &options));
+    assert_eq!(r"C:\cwd\foo", absolutize_from_with("C:foo", r"C:\cwd", &options));
+}
+
+#[test]
+fn drive_cwds_same_drive() {
+    let mut drive_cwds = DriveCwds::new();
+
+    assert_eq!(None, drive_cwds.insert(b'C', r"C:\other"));
+    assert_eq!(Some(br"C:\other".as_ref()), drive_cwds.get(b'c'));
+
+    let options = Options::new().drive_cwds(drive_cwds);
+
+    // the CWD is the current directory of its own drive
+    assert_eq!(r"C:\cwd\foo", absolutize_from_with("C:foo", r"C:\cwd", &options));
+    assert_eq!(r"C:\other\foo", absolutize_from_with("C:foo", r"D:\cwd", &options));
+    assert_eq!(r"C:foo\bar\baz\abc", absolutize_from_with(r"C:.\abc", r"foo\bar\baz", &options));
+}
+
+#[test]
+fn drive_cwds_virtually() {
+    let options = Options::new().drive_cwd(b'D', r"D:\d\cwd");
+
+    assert_eq!(
+        Ok(r"D:\d\foo".to_string()),
+        windows::absolutize_virtually_with(br"D:..\foo", br"D:\d", br"C:\cwd", &options).map(utf8)
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        windows::absolutize_virtually_with(br"D:..\foo", br"D:\d", br"C:\cwd", &Options::new())
+    );
+}
+
+#[test]
+fn prefix_kinds() {
+    // verbatim paths are literal
+    assert_eq!(r"\\?\C:\a\..\b", absolutize_from(r"\\?\C:\a\..\b", r"C:\cwd"));
+    assert_eq!(r"\\?\C:", absolutize_from(r"\\?\C:", r"C:\cwd"));
+    assert_eq!(
+        r"\\?\UNC\server\share\..\a",
+        absolutize_from(r"\\?\UNC\server\share\..\a", r"C:\cwd")
+    );
+    assert_eq!(r"\\?\pictures\.\a/b", absolutize_from(r"\\?\pictures\.\a/b", r"C:\cwd"));
+    assert!(matches!(windows::absolutize_from(br"\\?\C:\a\..\b", br"C:\cwd"), Cow::Borrowed(_)));
+
+    // device namespaces
+    assert_eq!(r"\\.\PIPE\x", absolutize_from(r"\\.\PIPE\a\..\x", r"C:\cwd"));
+    assert_eq!(r"\\.\PIPE\x", absolutize_from(r"\\.\PIPE\..\..\x", r"C:\cwd"));
+    assert_eq!(r"\\.\C:", absolutize_from(r"\\.\C:", r"C:\cwd"));
+    assert_eq!(r"\\.\PIPE\x", absolutize_from("//./PIPE/x", r"C:\cwd"));
+
+    // UNC
+    assert_eq!(r"\\server\share\", absolutize_from(r"\\server\share\..", r"C:\cwd"));
+    assert_eq!(r"\\server\share\x", absolutize_from(r"\\server\share\a\..\..\..\x", r"C:\cwd"));
+    assert_eq!(r"\\server\share\a\b", absolutize_from("//server/share/a/b", r"C:\cwd"));
+    assert_eq!(r"\\server\share\x", absolutize_from(r"\x", "//server/share/cwd"));
+    assert_eq!(r"\\server\share\x", absolutize_from(r"..\..\..\x", r"\\server\share\cwd"));
+}
+
+fn to_verbatim_from(path: &str, cwd: &str) -> String {
+    utf8(windows::to_verbatim_from(path.as_bytes(), cwd.as_bytes()))
+}
+
+fn simplify_verbatim(path: &str) -> String {
+    utf8(windows::simplify_verbatim(path.as_bytes()))
+}
+
+#[test]
+fn to_verbatim() {
+    assert_eq!(r"\\?\C:\cwd\a\b", to_verbatim_from(r"a\.\b", r"C:\cwd"));
+    assert_eq!(r"\\?\C:\a\b", to_verbatim_from("C:/a/b", r"C:\cwd"));
+    assert_eq!(r"\\?\C:\", to_verbatim_from("C:", r"C:\cwd"));
+    assert_eq!(r"\\?\UNC\server\share\b", to_verbatim_from(r"\\server\share\a\..\b", r"C:\cwd"));
+    assert_eq!(r"\\?\UNC\server\share\a", to_verbatim_from(r"a", "//server/share"));
+    assert_eq!(r"\\?\PIPE\x", to_verbatim_from(r"\\.\PIPE\x", r"C:\cwd"));
+    assert_eq!(r"\\?\C:\a\..\b", to_verbatim_from(r"\\?\C:\a\..\b", r"C:\cwd"));
+    assert_eq!(r"\cwd\a", to_verbatim_from("a", r"\cwd"));
+}
+
+#[test]
+fn simplify_verbatim_round_trip() {
+    assert_eq!(r"C:\a\b", simplify_verbatim(r"\\?\C:\a\b"));
+    assert_eq!(r"C:\", simplify_verbatim(r"\\?\C:\"));
+    assert_eq!(r"\\server\share\a", simplify_verbatim(r"\\?\UNC\server\share\a"));
+    assert_eq!(r"\\server\share", simplify_verbatim(r"\\?\UNC\server\share"));
+    assert_eq!(r"C:\a\b", simplify_verbatim(r"C:\a\b"));
+
+    for path in [r"C:\cwd\a\b", r"\\server\share\a", r"C:\"] {
+        assert_eq!(path, simplify_verbatim(&to_verbatim_from(path, r"C:\cwd")));
+    }
+}
+
+#[test]
+fn simplify_verbatim_kept() {
+    for path in [
+        r"\\?\C:",
+        r"\\?\C:\a\..\b",
+        r"\\?\C:\a\.\b",
+        r"\\?\C:\a\\b",
+        r"\\?\C:\a.",
+        r"\\?\C:\a \b",
+        r"\\?\C:\a/b",
+        r"\\?\C:\NUL",
+        r"\\?\C:\dir\con.txt",
+        r"\\?\C:\COM1 .log",
+        "\\\\?\\C:\\LPT\u{b9}",
+        r"\\?\UNC\server",
+        r"\\?\Volume{00000000-0000-0000-0000-000000000000}\a",
+    ] {
+        assert_eq!(path, simplify_verbatim(path), "{}", path);
+    }
+
+    let long = format!(r"\\?\C:\{}", "a".repeat(windows::MAX_PATH - 3));
+
+    assert_eq!(long, simplify_verbatim(&long));
+
+    let short = format!(r"\\?\C:\{}", "a".repeat(windows::MAX_PATH - 4));
+
+    assert_eq!(&short[4..], simplify_verbatim(&short));
+}
+
+fn normalize_win32(path: &str) -> String {
+    utf8(windows::normalize_win32(path.as_bytes()))
+}
+
+#[test]
+fn win32_normalization() {
+    assert_eq!(r"C:\a\b", normalize_win32(r"C:\a.\b"));
+    assert_eq!(r"C:\a\b", normalize_win32(r"C:\a\b. . ."));
+    assert_eq!(r"C:\a\b .\", normalize_win32(r"C:\a\b ..\"));
+    assert_eq!(r"C:\a\...\b", normalize_win32(r"C:\a\...\b"));
+    assert_eq!(r"C:\a\..", normalize_win32(r"C:\a\.."));
+    assert_eq!(r"\\server\share.\a", normalize_win32(r"\\server\share.\a."));
+    assert_eq!(r"\\?\C:\a.\b.", normalize_win32(r"\\?\C:\a.\b."));
+    assert!(matches!(windows::normalize_win32(br"C:\a\b"), Cow::Borrowed(_)));
+
+    let options = Options::new().win32_normalization(true);
+
+    assert_eq!(r"C:\cwd\file", absolutize_from_with("file. ", r"C:\cwd", &options));
+    assert_eq!(r"C:\cwd\file. ", absolutize_from_with("file. ", r"C:\cwd", &Options::new()));
+    assert_eq!(
+        Ok(r"C:\root\dir\file".to_string()),
+        windows::absolutize_virtually_with(br"dir.\file ", br"C:\root", br"C:\cwd", &options)
+            .map(utf8)
+    );
+}
+
+#[test]
+fn win32_validation() {
+    use path_absolutize::engine::windows::Win32IssueKind;
+
+    let issues = windows::win32_issues(br"C:\dir.\aux.txt\ok\file:stream\COM1 .log\name ");
+
+    assert_eq!(
+        vec![
+            (Win32IssueKind::TrailingDot, 2, b"dir.".as_ref()),
+            (Win32IssueKind::ReservedName, 3, b"aux.txt".as_ref()),
+            (Win32IssueKind::AlternateDataStream, 5, b"file:stream".as_ref()),
+            (Win32IssueKind::ReservedName, 6, b"COM1 .log".as_ref()),
+            (Win32IssueKind::TrailingSpace, 7, b"name ".as_ref()),
+        ],
+        issues.iter().map(|issue| (issue.kind, issue.index, issue.name)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(Ok(()), windows::validate_win32(br"C:\dir\CONSOLE\COM10\file.txt"));
+    assert_eq!(Ok(()), windows::validate_win32(br"D:file"));
+    assert_eq!(
+        Err(Error::Win32Name {
+            kind: Win32IssueKind::ReservedName, index: 0
+        }),
+        windows::validate_win32(b"nul")
+    );
+}
+
+#[test]
+fn ignore_case() {
+    assert!(windows::paths_equal_ignore_case(br"C:\Path\To", b"c:/path//to/"));
+    assert!(windows::paths_equal_ignore_case(br"\\Server\Share\a", br"\\SERVER\share\A"));
+    assert!(windows::paths_equal_ignore_case(
+        "C:\\\u{c4}\u{3a3}".as_bytes(),
+        "C:\\\u{e4}\u{3c3}".as_bytes()
+    ));
+    assert!(!windows::paths_equal_ignore_case(br"C:\a", br"D:\a"));
+    assert!(!windows::paths_equal_ignore_case(br"C:\a", br"\\?\C:\a"));
+    assert!(!windows::paths_equal_ignore_case(br"C:\a\..", br"C:\"));
+
+    // one-to-one mappings only
+    assert!(!windows::paths_equal_ignore_case("C:\\stra\u{df}e".as_bytes(), b"C:\\STRASSE"));
+
+    // mappings which Unicode added after the NTFS upcase tables were written are still applied
+    for (lower, upper) in
+        [('\u{10d0}', '\u{1c90}'), ('\u{a7c8}', '\u{a7c7}'), ('\u{a7d1}', '\u{a7d0}')]
+    {
+        assert!(windows::paths_equal_ignore_case(
+            format!("C:\\{}", lower).as_bytes(),
+            format!("C:\\{}", upper).as_bytes()
+        ));
+    }
+
+    // supplementary code points are kept, e.g. Deseret `U+10428` and `U+10400`
+    assert!(!windows::paths_equal_ignore_case(
+        "C:\\\u{10428}".as_bytes(),
+        "C:\\\u{10400}".as_bytes()
+    ));
+
+    // unpaired surrogates are compared as they are
+    let a = wtf8::from_wide(&[b'C' as u16, b':' as u16, b'\\' as u16, 0xD800, b'a' as u16]);
+    let b = wtf8::from_wide(&[b'c' as u16, b':' as u16, b'\\' as u16, 0xD800, b'A' as u16]);
+    let c = wtf8::from_wide(&[b'c' as u16, b':' as u16, b'\\' as u16, 0xD801, b'A' as u16]);
+
+    assert!(windows::paths_equal_ignore_case(&a, &b));
+    assert!(!windows::paths_equal_ignore_case(&a, &c));
+
+    assert!(windows::starts_with_ignore_case(br"C:\Root\a", br"c:\root"));
+    assert!(windows::starts_with_ignore_case(br"C:\Root\a", br"c:\root\"));
+    assert!(!windows::starts_with_ignore_case(br"C:\root2", br"C:\root"));
+    assert!(!windows::starts_with_ignore_case(br"C:\", br"C:\root"));
+}
+
+#[test]
+fn absolutize_virtually_component_aligned() {
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually(r"C:\root2\a", r"C:\root", r"C:\cwd")
+    );
+    assert_eq!(
+        Err(Error::OutsideVirtualRoot),
+        absolutize_virtually(r"\foo", r"C:\root", r"C:\cwd")
+    );
+    assert_eq!(
+        Ok(r"C:\ROOT\a".to_string()),
+        absolutize_virtually(r"C:\ROOT\a", r"c:\root", r"C:\cwd")
+    );
+    assert_eq!(Ok(r"C:\Root\a".to_string()), absolutize_virtually(r"c:a", r"C:\Root", r"C:\cwd"));
+
+    let path = wtf8::from_wide(&[
+        b'C' as u16,
+        b':' as u16,
+        b'\\' as u16,
+        b'r' as u16,
+        b'\\' as u16,
+        0xDC00,
+    ]);
+
+    assert!(windows::absolutize_virtually(&path, br"C:\R", br"C:\cwd").is_ok());
+}
+
+#[test]
+fn separator_style() {
+    use path_absolutize::engine::SeparatorStyle;
+
+    let forward = Options::new().separator_style(SeparatorStyle::ForwardSlash);
+    let backward = Options::new().separator_style(SeparatorStyle::Backslash);
+
+    assert_eq!("C:/cwd/a/b", absolutize_from_with(r"a\.\b", r"C:\cwd", &forward));
+    assert_eq!("C:/", absolutize_from_with("C:", r"C:\cwd", &forward));
+    assert_eq!("C:/a", absolutize_from_with(r"\a", r"C:\cwd", &forward));
+    assert_eq!("//server/share/a", absolutize_from_with(r"\\server\share\a", r"C:\cwd", &forward));
+    assert_eq!("//./PIPE/x", absolutize_from_with(r"\\.\PIPE\x", r"C:\cwd", &forward));
+    assert_eq!(r"\\?\C:\a/b", absolutize_from_with(r"\\?\C:\a/b", r"C:\cwd", &forward));
+
+    // the same length, so the input would be kept in the native style
+    assert_eq!("C:/a/b", absolutize_from_with("C:/a/b", r"C:\cwd", &Options::new()));
+    assert_eq!(r"C:\a\b", absolutize_from_with("C:/a/b", r"C:\cwd", &backward));
+    assert_eq!(r"\\server\share\a", absolutize_from_with("//server/share/a", r"C:\cwd", &backward));
+    assert_eq!(r"\\?\C:\a/b", absolutize_from_with(r"\\?\C:\a/b", r"C:\cwd", &backward));
+
+    assert_eq!(
+        Ok("C:/root/a".to_string()),
+        windows::absolutize_virtually_with(b"a", br"C:\root", br"C:\cwd", &forward).map(utf8)
+    );
+    assert_eq!(r"\\?\C:\cwd\a", utf8(windows::to_verbatim_from_with(b"a", br"C:\cwd", &forward)));
+}
+
+#[test]
+fn virtualize() {
+    use path_absolutize::engine::SeparatorStyle;
+
+    let virtualize = |path: &str, virtual_root: &str| {
+        windows::virtualize(path.as_bytes(), virtual_root.as_bytes(), br"C:\cwd").map(utf8)
+    };
+
+    assert_eq!(Ok(r"\a\b".to_string()), virtualize(r"C:\srv\root\a\b", r"C:\srv\root"));
+    assert_eq!(Ok(r"\a\b".to_string()), virtualize(r"c:\SRV\Root\a\.\x\..\b", r"C:\srv\root\"));
+    assert_eq!(Ok(r"\".to_string()), virtualize(r"C:\srv\root", r"C:\srv\root"));
+    assert_eq!(Ok(r"\srv\a".to_string()), virtualize(r"C:/srv/a", r"C:\"));
+    assert_eq!(Ok(r"\a".to_string()), virtualize(r"root\a", r"root"));
+    assert_eq!(Ok(r"\a".to_string()), virtualize(r"\\server\share\root\a", r"\\SERVER\share\root"));
+
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize(r"C:\srv\root2\a", r"C:\srv\root"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize(r"C:\srv\root\..\a", r"C:\srv\root"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize(r"D:\srv\root\a", r"C:\srv\root"));
+    assert_eq!(Err(Error::OutsideVirtualRoot), virtualize(r"\\server\share\a", r"C:\"));
+
+    let options = Options::new().separator_style(SeparatorStyle::ForwardSlash);
+
+    assert_eq!(
+        b"/a/b".as_ref(),
+        windows::virtualize_with(br"C:\srv\root\a\b", br"C:\srv\root", br"C:\cwd", &options)
+            .unwrap()
+            .as_ref()
+    );
+
+    // the inverse of `absolutize_virtually`
+    for path in [r"a\b", "a"] {
+        let real = absolutize_virtually(path, r"C:\srv\root", r"C:\cwd").unwrap();
+
+        assert_eq!(Ok(format!(r"\{}", path)), virtualize(&real, r"C:\srv\root"));
+    }
+}
+
+#[test]
+fn limits() {
+    use path_absolutize::engine::{Limit, Limits};
+
+    fn try_absolutize(path: &[u8], limits: Limits) -> Result<Cow<'_, [u8]>, Error> {
+        windows::try_absolutize_from_with(path, br"C:\cwd", &Options::new().limits(limits))
+    }
+
+    assert_eq!(
+        br"C:\cwd\a\b".as_ref(),
+        try_absolutize(br"a\.\b", Limits::new().max_components(3).max_output_length(10))
+            .unwrap()
+            .as_ref()
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::Components)),
+        try_absolutize(br"C:\a\b", Limits::new().max_components(3))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::OutputLength)),
+        try_absolutize(br"a\.\b", Limits::new().max_output_length(windows::MAX_PATH.min(9)))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::ParentDirs)),
+        try_absolutize(&br"a\..\".repeat(100_000), Limits::new().max_parent_dirs(64))
+    );
+    assert_eq!(
+        Err(Error::LimitExceeded(Limit::InputLength)),
+        windows::absolutize_virtually_with(
+            br"a\b",
+            br"C:\root",
+            br"C:\cwd",
+            &Options::new().limits(Limits::new().max_input_length(2))
+        )
+    );
+}
+
+#[test]
+fn case_fold_key() {
+    assert_eq!(
+        windows::case_fold_key(r"C:\A\ÄB".as_bytes()),
+        windows::case_fold_key("c:/a/äb".as_bytes())
+    );
+    assert_ne!(windows::case_fold_key(br"C:\a"), windows::case_fold_key(br"C:\b"));
+
+    // `ß` has no one-to-one uppercase mapping
+    assert_eq!("C:\\ß".as_bytes(), windows::case_fold_key("c:\\ß".as_bytes()).as_slice());
+
+    // `/` is not a separator in verbatim paths
+    assert_eq!(br"\\?\C:\A/B".as_ref(), windows::case_fold_key(br"\\?\c:\a/b").as_slice());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "std")]
+
+use std::path::Path;
+
+use path_absolutize::{AbsolutizeExplained, CwdSource};
+
+#[cfg(unix)]
+#[test]
+fn absolutize_from_explained() {
+    use path_absolutize::engine::explain::Step;
+
+    let explanation = Path::new("../a/./b").absolutize_from_explained("/x/y").unwrap();
+
+    assert_eq!(Path::new("/x/a/b"), explanation.path);
+    assert_eq!(Path::new("/x/y"), explanation.cwd);
+    assert_eq!(CwdSource::Given, explanation.cwd_source);
+    assert_eq!(
+        vec![
+            Step::ResolvedFromCwd {
+                cwd: b"/x/y".to_vec(), parent: true
+            },
+            Step::Kept {
+                name: b"a".to_vec()
+            },
+            Step::Kept {
+                name: b"b".to_vec()
+            },
+        ],
+        explanation.steps
+    );
+    assert_eq!(
+        "`../a/./b` -> `/x/a/b`\ncurrent working directory `/x/y`, given\n1. resolved from the \
+         parent of the current working directory `/x/y`\n2. kept `a`\n3. kept `b`",
+        explanation.to_string()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn absolutize_from_explained() {
+    let explanation = Path::new(r"\a\..\b").absolutize_from_explained(r"C:\x").unwrap();
+
+    assert_eq!(Path::new(r"C:\b"), explanation.path);
+    assert_eq!(CwdSource::Given, explanation.cwd_source);
+    assert_eq!(5, explanation.steps.len());
+}
+
+#[cfg(windows)]
+#[test]
+fn absolutize_from_explained_other_drive() {
+    let explanation = Path::new(r"D:a\b").absolutize_from_explained(r"C:\x").unwrap();
+
+    assert_eq!(Path::new(r"D:\a\b"), explanation.path);
+    assert_eq!(Path::new(r"D:\"), explanation.cwd);
+    assert_eq!(
+        CwdSource::DriveRoot {
+            drive: 'D'
+        },
+        explanation.cwd_source
+    );
+}
+
+#[cfg(not(feature = "unsafe_cache"))]
+#[test]
+fn absolutize_explained() {
+    use path_absolutize::Absolutize;
+
+    let path = Path::new("a/../b");
+
+    let explanation = path.absolutize_explained().unwrap();
+
+    assert_eq!(path.absolutize().unwrap(), explanation.path);
+    assert_eq!(CwdSource::current(), explanation.cwd_source);
+
+    #[cfg(not(any(feature = "once_cell_cache", feature = "lazy_static_cache")))]
+    {
+        assert_eq!(CwdSource::CurrentDir, explanation.cwd_source);
+        assert_eq!(std::env::current_dir().unwrap(), explanation.cwd);
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::{engine::http::RequestPolicy, map_request_path};
+
+#[cfg(unix)]
+#[test]
+fn map_request_path_unix() {
+    use path_absolutize::engine::http::TrailingSlash;
+
+    let policy = RequestPolicy::new();
+
+    assert_eq!(
+        Path::new("/srv/www/index.html"),
+        map_request_path("/index.html", "/srv/www", &policy).unwrap()
+    );
+    assert_eq!(
+        Path::new("/srv/www/a b/c.txt"),
+        map_request_path("/a%20b/./x/../c.txt", "/srv/www/", &policy).unwrap()
+    );
+    assert_eq!(Path::new("/srv/www"), map_request_path("/", "/srv/www", &policy).unwrap());
+    assert_eq!(
+        Path::new("/srv/www/docs"),
+        map_request_path("/docs/", "/srv/www", &policy).unwrap()
+    );
+
+    let kept =
+        map_request_path("/docs/", "/srv/www", &policy.clone().trailing_slash(TrailingSlash::Keep))
+            .unwrap();
+
+    assert_eq!(Path::new("/srv/www/docs/").as_os_str(), kept.as_os_str());
+
+    for url_path in
+        ["/../etc/passwd", "/%2e%2e/etc/passwd", "/..%2Fetc", "/.git/config", "/a%00", "/%FF", "/%"]
+    {
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            map_request_path(url_path, "/srv/www", &policy).unwrap_err().kind(),
+            "{:?}",
+            url_path
+        );
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn map_request_path_windows() {
+    let policy = RequestPolicy::new();
+
+    assert_eq!(
+        Path::new(r"C:\www\a\b.txt"),
+        map_request_path("/a/b.txt", r"C:\www", &policy).unwrap()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        map_request_path("/..%5Cx", r"C:\www", &policy).unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        map_request_path("/D:/x", r"C:\www", &policy).unwrap_err().kind()
+    );
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "std")]
+
+use std::{env, path::Path, sync::Arc, thread};
+
+use path_absolutize::MemoAbsolutizer;
+
+#[cfg(unix)]
+#[test]
+fn memo_absolutizer() {
+    let memo = MemoAbsolutizer::with_cwd(2, "/cwd");
+
+    assert!(memo.is_empty());
+    assert_eq!(2, memo.capacity());
+
+    let a = memo.absolutize("./a/../b").unwrap();
+
+    assert_eq!(Path::new("/cwd/b"), a.as_ref());
+    assert!(Arc::ptr_eq(&a, &memo.absolutize("./a/../b").unwrap()));
+    assert_eq!(1, memo.len());
+
+    let c = memo.absolutize("c").unwrap();
+
+    // `./a/../b` is used more recently than `c`
+    memo.absolutize("./a/../b").unwrap();
+    memo.absolutize("d").unwrap();
+
+    assert_eq!(2, memo.len());
+    assert!(Arc::ptr_eq(&a, &memo.absolutize("./a/../b").unwrap()));
+    assert!(!Arc::ptr_eq(&c, &memo.absolutize("c").unwrap()));
+}
+
+#[cfg(unix)]
+#[test]
+fn memo_absolutizer_cwd() {
+    let memo = MemoAbsolutizer::with_cwd(8, "/x");
+
+    let a = memo.absolutize("a").unwrap();
+
+    assert_eq!(0, memo.generation());
+
+    memo.set_cwd("/x");
+
+    assert_eq!(0, memo.generation());
+    assert!(Arc::ptr_eq(&a, &memo.absolutize("a").unwrap()));
+
+    memo.set_cwd("/y");
+
+    assert_eq!(1, memo.generation());
+    assert!(memo.is_empty());
+    assert_eq!(Path::new("/y"), memo.cwd().as_ref());
+    assert_eq!(Path::new("/y/a"), memo.absolutize("a").unwrap().as_ref());
+    assert_eq!(Path::new("/abs"), memo.absolutize("/abs/.").unwrap().as_ref());
+
+    memo.clear();
+
+    assert!(memo.is_empty());
+}
+
+#[cfg(windows)]
+#[test]
+fn memo_absolutizer() {
+    let memo = MemoAbsolutizer::with_cwd(2, r"C:\cwd");
+
+    let a = memo.absolutize(r".\a\..\b").unwrap();
+
+    assert_eq!(Path::new(r"C:\cwd\b"), a.as_ref());
+    assert!(Arc::ptr_eq(&a, &memo.absolutize(r".\a\..\b").unwrap()));
+
+    memo.set_cwd(r"D:\");
+
+    assert_eq!(Path::new(r"D:\b"), memo.absolutize(r".\a\..\b").unwrap().as_ref());
+}
+
+#[test]
+fn memo_absolutizer_no_capacity() {
+    let memo = MemoAbsolutizer::with_cwd(0, env::current_dir().unwrap());
+
+    let a = memo.absolutize("a").unwrap();
+
+    assert!(memo.is_empty());
+    assert!(!Arc::ptr_eq(&a, &memo.absolutize("a").unwrap()));
+}
+
+#[cfg(any(feature = "once_cell_cache", feature = "lazy_static_cache"))]
+#[test]
+fn memo_absolutizer_agrees_with_absolutize() {
+    use path_absolutize::Absolutize;
+
+    let memo = MemoAbsolutizer::new(4).unwrap();
+
+    for path in [".", "..", "./a/../b", "../a/./b/..", "a//b/"] {
+        assert_eq!(
+            Path::new(path).absolutize().unwrap().as_ref(),
+            memo.absolutize(path).unwrap().as_ref()
+        );
+    }
+
+    assert_eq!(0, memo.generation());
+}
+
+#[cfg(all(unix, not(any(feature = "once_cell_cache", feature = "lazy_static_cache"))))]
+#[test]
+fn memo_absolutizer_follows_cwd() {
+    use path_absolutize::Absolutize;
+
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    let cwd = env::current_dir().unwrap();
+
+    let memo = MemoAbsolutizer::new(4).unwrap();
+
+    for path in [".", "..", "./a/../b", "../a/./b/..", "a//b/"] {
+        assert_eq!(
+            Path::new(path).absolutize().unwrap().as_ref(),
+            memo.absolutize(path).unwrap().as_ref()
+        );
+    }
+
+    assert_eq!(cwd.join("a"), memo.absolutize("a").unwrap().as_ref());
+    assert_eq!(0, memo.generation());
+
+    env::set_current_dir("/").unwrap();
+
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    let a = memo.absolutize("a").unwrap();
+
+    env::set_current_dir(&cwd).unwrap();
+
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    assert_eq!(Path::new("/a"), a.as_ref());
+    assert_eq!(1, memo.generation());
+    assert_eq!(1, memo.len());
+}
+
+#[test]
+fn memo_absolutizer_threads() {
+    let root = env::current_dir().unwrap().join("root");
+
+    let memo = Arc::new(MemoAbsolutizer::with_cwd(16, &root));
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let memo = memo.clone();
+
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    let path = format!("{}/../{}", t, i % 32);
+
+                    let output = memo.absolutize(&path).unwrap();
+
+                    assert!(output.ends_with((i % 32).to_string()));
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert!(memo.len() <= 16);
+}
@@ -0,0 +1,116 @@
+#![cfg(all(unix, feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::MountTable;
+
+fn table() -> MountTable {
+    MountTable::new()
+        .mount("/assets", "/srv/static")
+        .unwrap()
+        .mount("uploads/", "/data/uploads")
+        .unwrap()
+        .mount("/assets/vendor", "/opt/vendor")
+        .unwrap()
+}
+
+#[test]
+fn mounts() {
+    let table = table();
+
+    let mounts: Vec<(&Path, &Path)> = table.mounts().collect();
+
+    assert_eq!(
+        vec![
+            (Path::new("/assets"), Path::new("/srv/static")),
+            (Path::new("/uploads"), Path::new("/data/uploads")),
+            (Path::new("/assets/vendor"), Path::new("/opt/vendor")),
+        ],
+        mounts
+    );
+}
+
+#[test]
+fn resolve() {
+    let table = table();
+
+    assert_eq!(Path::new("/srv/static/css/a.css"), table.resolve("/assets/css/a.css").unwrap());
+    assert_eq!(
+        Path::new("/srv/static/img/a.png"),
+        table.resolve("/assets/css/../img/a.png").unwrap()
+    );
+    assert_eq!(Path::new("/srv/static"), table.resolve("/assets").unwrap());
+    assert_eq!(Path::new("/srv/static"), table.resolve("/assets/").unwrap());
+    assert_eq!(Path::new("/data/uploads/x"), table.resolve("uploads/./x").unwrap());
+    assert_eq!(Path::new("/data/uploads/x"), table.resolve("./uploads//x").unwrap());
+}
+
+#[test]
+fn resolve_longest_prefix() {
+    let table = table();
+
+    assert_eq!(
+        Path::new("/opt/vendor/jquery.js"),
+        table.resolve("/assets/vendor/jquery.js").unwrap()
+    );
+    assert_eq!(
+        Path::new("/srv/static/vendor2/a.js"),
+        table.resolve("/assets/vendor2/a.js").unwrap()
+    );
+}
+
+#[test]
+fn resolve_not_found() {
+    let table = table();
+
+    assert_eq!(ErrorKind::NotFound, table.resolve("/").unwrap_err().kind());
+    assert_eq!(ErrorKind::NotFound, table.resolve("/assets2/a").unwrap_err().kind());
+    assert_eq!(ErrorKind::NotFound, table.resolve("/other/a").unwrap_err().kind());
+    assert_eq!(ErrorKind::NotFound, table.resolve("/../assets/a").unwrap_err().kind());
+}
+
+#[test]
+fn resolve_jail() {
+    let table = table();
+
+    assert_eq!(ErrorKind::InvalidInput, table.resolve("/assets/..").unwrap_err().kind());
+    assert_eq!(ErrorKind::InvalidInput, table.resolve("/assets/../uploads/x").unwrap_err().kind());
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        table.resolve("/assets/css/../../../etc/passwd").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        table.resolve("/assets/vendor/../a.css").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        table.resolve("/uploads/../../data/uploads2").unwrap_err().kind()
+    );
+}
+
+#[test]
+fn resolve_root_mount() {
+    let table =
+        MountTable::new().mount("/", "/srv/www").unwrap().mount("/api", "/srv/api").unwrap();
+
+    assert_eq!(Path::new("/srv/www/index.html"), table.resolve("/index.html").unwrap());
+    assert_eq!(Path::new("/srv/www"), table.resolve("/").unwrap());
+    assert_eq!(Path::new("/srv/api/v1"), table.resolve("/api/v1").unwrap());
+    assert_eq!(ErrorKind::InvalidInput, table.resolve("/../etc").unwrap_err().kind());
+}
+
+#[test]
+fn to_virtual() {
+    let table = table();
+
+    assert_eq!(Path::new("/assets/css/a.css"), table.to_virtual("/srv/static/css/a.css").unwrap());
+    assert_eq!(Path::new("/assets"), table.to_virtual("/srv/static").unwrap());
+    assert_eq!(Path::new("/assets/vendor/a.js"), table.to_virtual("/opt/vendor/./a.js").unwrap());
+    assert_eq!(Path::new("/uploads/x"), table.to_virtual("/data/uploads/y/../x").unwrap());
+    assert_eq!(ErrorKind::NotFound, table.to_virtual("/srv/static2/a").unwrap_err().kind());
+
+    for path in ["/assets/css/a.css", "/uploads/x", "/assets/vendor/a.js"] {
+        assert_eq!(Path::new(path), table.to_virtual(table.resolve(path).unwrap()).unwrap());
+    }
+}
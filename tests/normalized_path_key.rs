@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use path_absolutize::{NormalizedPathKey, NormalizedPathMap};
+
+#[cfg(unix)]
+#[test]
+fn normalized_path_key_unix() {
+    let keys: HashSet<NormalizedPathKey> = ["./a/b", "a/./b", "/cwd/a/b", "/cwd/x/../a/b"]
+        .iter()
+        .map(|path| NormalizedPathKey::new_from(path, "/cwd").unwrap())
+        .collect();
+
+    assert_eq!(1, keys.len());
+
+    let a = NormalizedPathKey::new_from("./a/b", "/cwd").unwrap();
+
+    assert_eq!(Path::new("./a/b"), a.original());
+    assert_eq!(Path::new("/cwd/a/b"), a.normalized());
+    assert_eq!("./a/b", a.to_string());
+
+    assert_ne!(a, NormalizedPathKey::new_from("./A/b", "/cwd").unwrap());
+    assert!(a < NormalizedPathKey::new_from("/cwd/a/c", "/cwd").unwrap());
+}
+
+#[cfg(windows)]
+#[test]
+fn normalized_path_key_windows() {
+    let keys: HashSet<NormalizedPathKey> = [r"C:\A", r"c:\a", r"C:/a", r"C:\x\..\A\."]
+        .iter()
+        .map(|path| NormalizedPathKey::new_from(path, r"C:\cwd").unwrap())
+        .collect();
+
+    assert_eq!(1, keys.len());
+
+    let a = NormalizedPathKey::new_from(r".\a", r"C:\cwd").unwrap();
+    let b = NormalizedPathKey::new_from(r"C:\CWD\A", r"C:\cwd").unwrap();
+
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn normalized_path_map() {
+    #[cfg(feature = "unsafe_cache")]
+    unsafe {
+        path_absolutize::update_cwd()
+    };
+
+    let cwd = std::env::current_dir().unwrap();
+
+    let mut hash_map = HashMap::new();
+
+    assert_eq!(None, hash_map.insert_path("./a/b", 1).unwrap());
+    assert_eq!(Some(1), hash_map.insert_path(cwd.join("a/b"), 2).unwrap());
+    assert_eq!(1, hash_map.len());
+    assert_eq!(Some(&2), hash_map.get_path("a/./b").unwrap());
+    assert_eq!(Path::new("./a/b"), hash_map.keys().next().unwrap().original());
+
+    *hash_map.get_path_mut("a/b").unwrap().unwrap() += 1;
+
+    assert!(hash_map.contains_path("x/../a/b").unwrap());
+    assert_eq!(Some(3), hash_map.remove_path("a/b").unwrap());
+    assert!(!hash_map.contains_path("a/b").unwrap());
+
+    let mut btree_map = BTreeMap::new();
+
+    btree_map.insert_path("b", 2).unwrap();
+    btree_map.insert_path("./a", 1).unwrap();
+
+    assert_eq!(vec![&1, &2], btree_map.values().collect::<Vec<_>>());
+    assert_eq!(Some(&1), btree_map.get_path(cwd.join("a")).unwrap());
+}
@@ -0,0 +1,99 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::path::Path;
+
+use path_absolutize::{Absolutize, PathTrie};
+
+#[cfg(unix)]
+#[test]
+fn path_trie_unix() {
+    let mut trie = PathTrie::new();
+
+    assert!(trie.is_empty());
+
+    assert_eq!(None, trie.insert("/srv", "srv").unwrap());
+    assert_eq!(None, trie.insert("/srv/www/./public", "public").unwrap());
+    assert_eq!(None, trie.insert("/srv/www/private/", "private").unwrap());
+    assert_eq!(Some("srv"), trie.insert("/srv/x/..", "root of srv").unwrap());
+
+    assert_eq!(3, trie.len());
+    assert_eq!(Some(&"public"), trie.get("/srv/www/public").unwrap());
+    assert_eq!(None, trie.get("/srv/www").unwrap());
+
+    assert_eq!(
+        Some((Path::new("/srv/www/public"), &"public")),
+        trie.longest_prefix("/srv/www/public/../public/index.html").unwrap()
+    );
+    assert_eq!(
+        Some((Path::new("/srv"), &"root of srv")),
+        trie.longest_prefix("/srv/www/publicity").unwrap()
+    );
+    assert_eq!(None, trie.longest_prefix("/etc/passwd").unwrap());
+    assert_eq!(None, trie.longest_prefix("/").unwrap());
+
+    assert!(trie.contains_subtree("/").unwrap());
+    assert!(trie.contains_subtree("/srv/www").unwrap());
+    assert!(trie.contains_subtree("/srv/www/private").unwrap());
+    assert!(!trie.contains_subtree("/srv/www/private/a").unwrap());
+    assert!(!trie.contains_subtree("/srv/w").unwrap());
+
+    assert_eq!(
+        vec![
+            (Path::new("/srv"), &"root of srv"),
+            (Path::new("/srv/www/private"), &"private"),
+            (Path::new("/srv/www/public"), &"public"),
+        ],
+        trie.iter().collect::<Vec<_>>()
+    );
+
+    assert_eq!(Some("private"), trie.remove("/srv/www/private").unwrap());
+    assert_eq!(None, trie.remove("/srv/www").unwrap());
+    assert!(!trie.contains_subtree("/srv/www/private").unwrap());
+    assert!(trie.contains_subtree("/srv/www").unwrap());
+
+    assert_eq!(Some("public"), trie.remove("/srv/www/public").unwrap());
+    assert!(!trie.contains_subtree("/srv/www").unwrap());
+    assert_eq!(1, trie.len());
+
+    trie.insert("/", "root").unwrap();
+
+    assert_eq!(Some((Path::new("/"), &"root")), trie.longest_prefix("/etc").unwrap());
+}
+
+#[cfg(windows)]
+#[test]
+fn path_trie_windows() {
+    let mut trie = PathTrie::new();
+
+    trie.insert(r"C:\Users\Public", 1).unwrap();
+    trie.insert(r"\\Server\Share", 2).unwrap();
+
+    assert_eq!(
+        Some((Path::new(r"C:\Users\Public"), &1)),
+        trie.longest_prefix(r"c:/users/PUBLIC/a.txt").unwrap()
+    );
+    assert_eq!(
+        Some((Path::new(r"\\Server\Share\"), &2)),
+        trie.longest_prefix(r"\\server\share\x").unwrap()
+    );
+    assert_eq!(None, trie.longest_prefix(r"D:\Users\Public").unwrap());
+    assert!(trie.contains_subtree(r"c:\users").unwrap());
+    assert_eq!(Some(1), trie.insert(r"C:\USERS\public", 3).unwrap());
+    assert_eq!(2, trie.len());
+}
+
+#[test]
+fn path_trie_relative() {
+    let mut trie = PathTrie::new();
+
+    trie.insert("a/b", ()).unwrap();
+
+    let path = Path::new("a/b/c").absolutize().unwrap();
+
+    assert_eq!(
+        Path::new("a/b").absolutize().unwrap().as_ref(),
+        trie.longest_prefix(path).unwrap().unwrap().0
+    );
+    assert!(trie.contains_subtree("./a").unwrap());
+    assert_eq!(1, (&trie).into_iter().count());
+}
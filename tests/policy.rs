@@ -0,0 +1,48 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::{
+    absolutize_virtually_with_policy,
+    engine::policy::{PathPolicy, PolicyViolationKind},
+    path_policy_violations,
+};
+
+#[cfg(unix)]
+#[test]
+fn path_policy_unix() {
+    let policy = PathPolicy::web_upload();
+
+    assert_eq!(
+        vec![(1, PolicyViolationKind::HiddenComponent), (2, PolicyViolationKind::ParentDir)],
+        path_policy_violations(Path::new("/.git/../a"), &policy)
+    );
+
+    assert_eq!(
+        Path::new("/srv/uploads/a/b.jpg"),
+        absolutize_virtually_with_policy(Path::new("a/b.jpg"), "/srv/uploads", &policy).unwrap()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        absolutize_virtually_with_policy(Path::new("a/.env"), "/srv/uploads", &policy)
+            .unwrap_err()
+            .kind()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn path_policy_windows() {
+    let policy = PathPolicy::web_upload();
+
+    assert_eq!(
+        vec![(2, PolicyViolationKind::WindowsReservedName)],
+        path_policy_violations(Path::new(r"C:\nul.txt"), &policy)
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        absolutize_virtually_with_policy(Path::new(r"a\CON"), r"C:\uploads", &policy)
+            .unwrap_err()
+            .kind()
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{env, io::ErrorKind, path::Path};
+
+use path_absolutize::Rebase;
+
+#[cfg(unix)]
+#[test]
+fn rebase_under() {
+    assert_eq!(
+        Path::new("/tmp/pkg/usr/lib/x"),
+        Path::new("/usr/lib/x").rebase_under("/tmp/pkg").unwrap()
+    );
+    assert_eq!(
+        Path::new("/tmp/pkg/usr/x"),
+        Path::new("/usr/lib/../x/.").rebase_under("/tmp/pkg/").unwrap()
+    );
+    assert_eq!(Path::new("/tmp/pkg/x"), Path::new("/../x").rebase_under("/tmp/./pkg").unwrap());
+    assert_eq!(Path::new("/tmp/pkg"), Path::new("/").rebase_under("/tmp/pkg").unwrap());
+    assert_eq!(Path::new("/usr/lib"), Path::new("/usr/lib").rebase_under("/").unwrap());
+}
+
+#[test]
+fn rebase_under_relative() {
+    let cwd = env::current_dir().unwrap();
+
+    let staging_root = cwd.join("pkg");
+
+    let mut expected = staging_root.clone();
+
+    for component in cwd.components().skip(1) {
+        expected.push(component);
+    }
+
+    expected.push("x");
+
+    assert_eq!(expected, Path::new("x").rebase_under("pkg").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn strip_staging_root() {
+    assert_eq!(
+        Path::new("/usr/lib/x"),
+        Path::new("/tmp/pkg/usr/lib/x").strip_staging_root("/tmp/pkg").unwrap()
+    );
+    assert_eq!(
+        Path::new("/usr/x"),
+        Path::new("/tmp/pkg/usr/lib/../x").strip_staging_root("/tmp/pkg/").unwrap()
+    );
+    assert_eq!(Path::new("/"), Path::new("/tmp/pkg").strip_staging_root("/tmp/pkg").unwrap());
+    assert_eq!(Path::new("/usr/lib"), Path::new("/usr/lib").strip_staging_root("/").unwrap());
+
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        Path::new("/tmp/pkg2/usr").strip_staging_root("/tmp/pkg").unwrap_err().kind()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        Path::new("/tmp/pkg/../usr").strip_staging_root("/tmp/pkg").unwrap_err().kind()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn round_trip() {
+    for path in ["/", "/usr/lib/x", "/etc/passwd"] {
+        let path = Path::new(path);
+
+        let staged = path.to_path_buf().rebase_under("/tmp/pkg").unwrap();
+
+        assert_eq!(path, staged.strip_staging_root("/tmp/pkg").unwrap());
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn rebase_under_windows() {
+    assert_eq!(
+        Path::new(r"D:\stage\Program Files\x"),
+        Path::new(r"C:\Program Files\x").rebase_under(r"D:\stage").unwrap()
+    );
+    assert_eq!(
+        Path::new(r"D:\stage\Program Files\x"),
+        Path::new(r"E:\Program Files\..\Program Files\x").rebase_under(r"D:\stage").unwrap()
+    );
+    assert_eq!(
+        Path::new(r"D:\stage\Program Files\x"),
+        Path::new(r"D:\stage\Program Files\x")
+            .strip_staging_root(r"d:\STAGE")
+            .unwrap()
+            .rebase_under(r"D:\stage")
+            .unwrap()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn strip_staging_root_windows() {
+    assert_eq!(
+        Path::new(r"\Program Files\x"),
+        Path::new(r"D:\stage\Program Files\x").strip_staging_root(r"d:\STAGE").unwrap()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        Path::new(r"C:\stage\x").strip_staging_root(r"D:\stage").unwrap_err().kind()
+    );
+}
@@ -0,0 +1,100 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{env, path::Path};
+
+use path_absolutize::PrefixRemapper;
+
+#[cfg(unix)]
+#[test]
+fn remap() {
+    let remapper = PrefixRemapper::new()
+        .rule("/home/ci", "/build")
+        .unwrap()
+        .rule("/home/ci/.cargo/registry", "/cargo")
+        .unwrap();
+
+    assert_eq!(
+        Path::new("/build/src/lib.rs"),
+        remapper.remap(Path::new("/home/ci/src/lib.rs")).unwrap()
+    );
+    assert_eq!(Path::new("/build"), remapper.remap(Path::new("/home/ci")).unwrap());
+    assert_eq!(Path::new("/build/src"), remapper.remap(Path::new("/home/ci/./a/../src/")).unwrap());
+    assert_eq!(
+        Path::new("/cargo/serde/lib.rs"),
+        remapper.remap(Path::new("/home/ci/.cargo/registry/serde/lib.rs")).unwrap()
+    );
+    assert_eq!(
+        Path::new("/build/.cargo/registry2"),
+        remapper.remap(Path::new("/home/ci/.cargo/registry2")).unwrap()
+    );
+
+    // component-aligned
+    assert_eq!(Path::new("/home/ci2/src"), remapper.remap(Path::new("/home/ci2/src")).unwrap());
+    assert_eq!(Path::new("/home/c"), remapper.remap(Path::new("/home/c")).unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn remap_order() {
+    let remapper = PrefixRemapper::new()
+        .rule("/home/ci", "/first")
+        .unwrap()
+        .rule("/home/ci/", "/second")
+        .unwrap();
+
+    assert_eq!(Path::new("/first/x"), remapper.remap(Path::new("/home/ci/x")).unwrap());
+}
+
+#[test]
+fn remap_relative() {
+    let cwd = env::current_dir().unwrap();
+
+    let remapper = PrefixRemapper::new().rule(".", ".").unwrap();
+
+    assert_eq!(Some((cwd.as_path(), Path::new("."))), remapper.rules().next());
+    assert_eq!(
+        Path::new(".").join("src").join("lib.rs"),
+        remapper.remap(&Path::new("src").join("lib.rs")).unwrap()
+    );
+    assert_eq!(Path::new("."), remapper.remap(Path::new(".")).unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn unmap() {
+    let remapper = PrefixRemapper::new()
+        .rule("/home/ci", "/build")
+        .unwrap()
+        .rule("/home/ci/.cargo/registry", "/build/registry")
+        .unwrap();
+
+    assert_eq!(Path::new("/home/ci/src/lib.rs"), remapper.unmap(Path::new("/build/src/lib.rs")));
+    assert_eq!(
+        Path::new("/home/ci/.cargo/registry/serde"),
+        remapper.unmap(Path::new("/build/registry/serde"))
+    );
+    assert_eq!(Path::new("/build2/src"), remapper.unmap(Path::new("/build2/src")));
+
+    for path in ["/home/ci/src/lib.rs", "/home/ci/.cargo/registry/serde/lib.rs", "/home/ci"] {
+        let path = Path::new(path);
+
+        assert_eq!(path, remapper.unmap(&remapper.remap(path).unwrap()));
+    }
+}
+
+#[cfg(windows)]
+#[test]
+fn remap_windows() {
+    let remapper = PrefixRemapper::new().rule(r"C:\Users\CI", r"\build").unwrap();
+
+    assert_eq!(
+        Path::new(r"\build\src\lib.rs"),
+        remapper.remap(Path::new(r"c:\users\ci\src\lib.rs")).unwrap()
+    );
+    assert_eq!(Path::new(r"\build\src"), remapper.remap(Path::new(r"C:/Users/CI/src")).unwrap());
+    assert_eq!(
+        Path::new(r"C:\Users\CI2\src"),
+        remapper.remap(Path::new(r"C:\Users\CI2\src")).unwrap()
+    );
+    assert_eq!(Path::new(r"C:\Users\CI\src"), remapper.unmap(Path::new(r"\build\src")));
+}
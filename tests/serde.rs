@@ -0,0 +1,218 @@
+#![cfg(all(unix, feature = "serde", not(feature = "unsafe_cache")))]
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use path_absolutize::{serde::AbsolutizeSeed, AbsolutePathBuf};
+use serde::{
+    de::{
+        value::{BytesDeserializer, Error, StrDeserializer},
+        DeserializeSeed, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, ser,
+    ser::Impossible,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A deserializer of a string or nothing, which stands for a field of a format like JSON.
+struct Value(Option<&'static str>);
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Some(s) => visitor.visit_str(s),
+            None => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Some(s) => visitor.visit_some(str_deserializer(s)),
+            None => visitor.visit_none(),
+        }
+    }
+}
+
+fn str_deserializer(s: &str) -> StrDeserializer<'_, Error> {
+    s.into_deserializer()
+}
+
+/// A serializer which accepts only a string or nothing, which is enough for paths.
+struct ValueSerializer;
+
+macro_rules! unsupported {
+    ($($name:ident($($ty:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $name(self, $(_: $ty),*) -> Result<$ok, Error> {
+                Err(ser::Error::custom("unsupported"))
+            }
+        )*
+    };
+}
+
+impl Serializer for ValueSerializer {
+    type Error = Error;
+    type Ok = Option<String>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeStruct = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+
+    unsupported! {
+        serialize_bool(bool) -> Self::Ok;
+        serialize_i8(i8) -> Self::Ok;
+        serialize_i16(i16) -> Self::Ok;
+        serialize_i32(i32) -> Self::Ok;
+        serialize_i64(i64) -> Self::Ok;
+        serialize_u8(u8) -> Self::Ok;
+        serialize_u16(u16) -> Self::Ok;
+        serialize_u32(u32) -> Self::Ok;
+        serialize_u64(u64) -> Self::Ok;
+        serialize_f32(f32) -> Self::Ok;
+        serialize_f64(f64) -> Self::Ok;
+        serialize_char(char) -> Self::Ok;
+        serialize_bytes(&[u8]) -> Self::Ok;
+        serialize_unit() -> Self::Ok;
+        serialize_unit_struct(&'static str) -> Self::Ok;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Self::Ok;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Error> {
+        Err(ser::Error::custom("unsupported"))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Error> {
+        Err(ser::Error::custom("unsupported"))
+    }
+}
+
+#[test]
+fn deserialize_with() {
+    let cwd = env::current_dir().unwrap();
+
+    assert_eq!(
+        cwd.join("path/to/456"),
+        path_absolutize::serde::deserialize(str_deserializer("./path/to/123/../456")).unwrap()
+    );
+    assert_eq!(
+        Some(PathBuf::from("/path/to/out")),
+        path_absolutize::serde::option::deserialize(Value(Some("/path/to/./out"))).unwrap()
+    );
+    assert_eq!(
+        cwd.join("cache"),
+        AbsolutePathBuf::deserialize(str_deserializer("cache")).unwrap().as_path()
+    );
+}
+
+#[test]
+fn deserialize_with_option_none() {
+    assert_eq!(None, path_absolutize::serde::option::deserialize(Value(None)).unwrap());
+}
+
+#[test]
+fn deserialize_bytes() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let deserializer: BytesDeserializer<Error> = BytesDeserializer::new(b"/path/to/\xFF/../x");
+
+    assert_eq!(
+        Path::new(OsStr::from_bytes(b"/path/to/x")),
+        path_absolutize::serde::deserialize(deserializer).unwrap()
+    );
+}
+
+#[test]
+fn round_trip() {
+    let cache = AbsolutePathBuf::new("/path/to/./cache").unwrap();
+
+    assert_eq!(Some("/path/to/cache".to_string()), cache.serialize(ValueSerializer).unwrap());
+    assert_eq!(
+        Some("/path/to/input".to_string()),
+        path_absolutize::serde::serialize(&Path::new("/path/to/input"), ValueSerializer).unwrap()
+    );
+    assert_eq!(
+        Some("/path/to/out".to_string()),
+        path_absolutize::serde::option::serialize(&Some("/path/to/out"), ValueSerializer).unwrap()
+    );
+    assert_eq!(
+        None,
+        path_absolutize::serde::option::serialize(&None::<&Path>, ValueSerializer).unwrap()
+    );
+
+    let serialized = cache.serialize(ValueSerializer).unwrap().unwrap();
+
+    assert_eq!(cache, AbsolutePathBuf::deserialize(str_deserializer(&serialized)).unwrap());
+}
+
+#[test]
+fn seed_from_base() {
+    let path =
+        AbsolutizeSeed::from_base("/etc/app").unwrap().deserialize(Value(Some("../data/./123")));
+
+    assert_eq!(Path::new("/etc/data/123"), path.unwrap());
+}
+
+#[test]
+fn seed_from_relative_base() {
+    let path = AbsolutizeSeed::from_base("app").unwrap().deserialize(str_deserializer("data"));
+
+    assert_eq!(env::current_dir().unwrap().join("app/data"), path.unwrap());
+}
+
+#[test]
+fn serialize_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let path = AbsolutePathBuf::new(OsStr::from_bytes(b"/path/to/\xFF")).unwrap();
+
+    assert!(path.serialize(ValueSerializer).is_err());
+}
+
+#[test]
+fn deserialize_error() {
+    let err = path_absolutize::serde::deserialize(Value(None)).unwrap_err();
+
+    assert!(err.to_string().contains("a path string"), "{}", err);
+}
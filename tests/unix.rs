@@ -1,4 +1,4 @@
-#![cfg(all(unix, not(feature = "unsafe_cache")))]
+#![cfg(all(unix, feature = "std", not(feature = "unsafe_cache")))]
 
 use std::{env, io::ErrorKind, path::Path};
 
@@ -0,0 +1,37 @@
+#![cfg(all(feature = "std", not(feature = "unsafe_cache")))]
+
+use std::{io::ErrorKind, path::Path};
+
+use path_absolutize::{virtualize, Absolutize};
+
+#[cfg(unix)]
+#[test]
+fn virtualize_unix() {
+    assert_eq!(Path::new("/a/b"), virtualize(Path::new("/srv/root/a/b"), "/srv/root").unwrap());
+    assert_eq!(Path::new("/"), virtualize(Path::new("/srv/root/a/.."), "/srv/root").unwrap());
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        virtualize(Path::new("/srv/root2/a"), "/srv/root").unwrap_err().kind()
+    );
+
+    let real = Path::new("a/../b").absolutize_virtually("/srv/root").unwrap();
+
+    assert_eq!(Path::new("/b"), virtualize(&real, "/srv/root").unwrap());
+}
+
+#[cfg(windows)]
+#[test]
+fn virtualize_windows() {
+    assert_eq!(
+        Path::new(r"\a\b"),
+        virtualize(Path::new(r"C:\Srv\Root\a\b"), r"c:\srv\root").unwrap()
+    );
+    assert_eq!(
+        ErrorKind::InvalidInput,
+        virtualize(Path::new(r"C:\srv\root2\a"), r"C:\srv\root").unwrap_err().kind()
+    );
+
+    let real = Path::new(r"a\..\b").absolutize_virtually(r"C:\srv\root").unwrap();
+
+    assert_eq!(Path::new(r"\b"), virtualize(&real, r"C:\srv\root").unwrap());
+}
@@ -1,14 +1,12 @@
-#![cfg(all(windows, not(feature = "unsafe_cache")))]
-
-#[macro_use]
-extern crate slash_formatter;
+#![cfg(all(windows, feature = "std", not(feature = "unsafe_cache")))]
+#![allow(clippy::needless_borrow, clippy::redundant_pattern_matching)]
 
 use std::{
     env,
     path::{Path, PathBuf},
 };
 
-use path_absolutize::{path_dedot::ParsePrefix, Absolutize};
+use path_absolutize::{path_dedot::ParsePrefix, Absolutize, Verbatim};
 
 #[test]
 fn absolutize_lv0_1() {
@@ -198,72 +196,51 @@ fn absolutize_lv3() {
     }
 }
 
-#[test]
-fn absolutize_lv4() {
+/// The current directory of a drive which is not the drive of the CWD, and the drive.
+fn other_drive_cwd() -> (PathBuf, &'static str) {
     let cwd = env::current_dir().unwrap();
 
     let cwd_prefix = cwd.get_path_prefix().unwrap();
 
     let target_prefix = if cwd_prefix.as_os_str().ne("C:") { "C:" } else { "D:" };
 
-    let target = PathBuf::from(format!(r"{}123\567", target_prefix));
+    let drive_cwd = match env::var_os(format!("={}", target_prefix)) {
+        Some(drive_cwd) => PathBuf::from(drive_cwd),
+        None => PathBuf::from(format!(r"{}\", target_prefix)),
+    };
+
+    (drive_cwd, target_prefix)
+}
+
+#[test]
+fn absolutize_lv4() {
+    let (drive_cwd, target_prefix) = other_drive_cwd();
 
-    let cwd = cwd.to_str().unwrap();
+    let target = PathBuf::from(format!(r"{}123\567", target_prefix));
 
-    let path = PathBuf::from(backslash!(
-        target_prefix,
-        &cwd[cwd_prefix.as_os_str().to_str().unwrap().len()..],
-        r"123\567"
-    ));
+    let path = drive_cwd.join(r"123\567");
 
     assert_eq!(path.to_str().unwrap(), target.absolutize().unwrap().to_str().unwrap());
 }
 
 #[test]
 fn absolutize_lv5_1() {
-    let cwd = env::current_dir().unwrap();
-
-    let cwd_prefix = cwd.get_path_prefix().unwrap();
-
-    let target_prefix = if cwd_prefix.as_os_str().ne("C:") { "C:" } else { "D:" };
+    let (drive_cwd, target_prefix) = other_drive_cwd();
 
     let target = PathBuf::from(format!(r"{}.\123\567", target_prefix));
 
-    let cwd = cwd.to_str().unwrap();
-
-    let path = PathBuf::from(backslash!(
-        target_prefix,
-        &cwd[cwd_prefix.as_os_str().to_str().unwrap().len()..],
-        r"123\567"
-    ));
+    let path = drive_cwd.join(r"123\567");
 
     assert_eq!(path.to_str().unwrap(), target.absolutize().unwrap().to_str().unwrap());
 }
 
 #[test]
 fn absolutize_lv5_2() {
-    let cwd = env::current_dir().unwrap();
-
-    let cwd_prefix = cwd.get_path_prefix().unwrap();
-
-    let target_prefix = if cwd_prefix.as_os_str().ne("C:") { "C:" } else { "D:" };
+    let (drive_cwd, target_prefix) = other_drive_cwd();
 
     let target = PathBuf::from(format!(r"{}..\123\567", target_prefix));
 
-    let cwd_parent = cwd.parent();
-
-    let path = match cwd_parent {
-        Some(cwd_parent) => {
-            let cwd_parent = cwd_parent.to_str().unwrap();
-
-            PathBuf::from(backslash!(
-                target_prefix,
-                &cwd_parent[cwd_prefix.as_os_str().to_str().unwrap().len()..],
-                r"123\567"
-            ))
-        },
-        None => PathBuf::from(backslash!(target_prefix, r"123\567")),
-    };
+    let path = drive_cwd.parent().unwrap_or(&drive_cwd).join(r"123\567");
 
     assert_eq!(path.to_str().unwrap(), target.absolutize().unwrap().to_str().unwrap());
 }
@@ -451,3 +428,68 @@ fn absolutize_virtually_3() {
 
     assert!(matches!(p.absolutize_virtually(r"D:\"), Err(_)));
 }
+
+#[test]
+fn prefix_matrix_share_root() {
+    let cases = [
+        (r"\\server\share\..", r"\\server\share\"),
+        (r"\\server\share\..\..\a", r"\\server\share\a"),
+        (r"\\server\share\a\..\..\b", r"\\server\share\b"),
+        ("//server/share/../a", r"\\server\share\a"),
+        (r"\\.\PIPE\..\x", r"\\.\PIPE\x"),
+        (r"\\.\PIPE\a\..\..\x", r"\\.\PIPE\x"),
+        (r"C:\..\..\a", r"C:\a"),
+    ];
+
+    for (path, expected) in cases {
+        assert_eq!(
+            expected,
+            Path::new(path).absolutize_from(r"C:\cwd").unwrap().to_str().unwrap(),
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn prefix_matrix_verbatim() {
+    let cases = [
+        r"\\?\C:\a\..\b",
+        r"\\?\C:\a\.\b\",
+        r"\\?\C:",
+        r"\\?\UNC\server\share\..\a",
+        r"\\?\UNC\server\share",
+        r"\\?\Volume{00000000-0000-0000-0000-000000000000}\a\..",
+        r"\\.\C:",
+        r"\\.\PIPE\",
+    ];
+
+    for path in cases {
+        assert_eq!(
+            path,
+            Path::new(path).absolutize_from(r"C:\cwd").unwrap().to_str().unwrap(),
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn verbatim() {
+    let p = Path::new(r"C:\path\to\..\123");
+
+    assert_eq!(r"\\?\C:\path\123", p.to_verbatim().unwrap().to_str().unwrap());
+    assert_eq!(r"C:\path\123", p.to_verbatim().unwrap().simplify_verbatim().to_str().unwrap());
+    assert_eq!(r"\\?\C:\NUL", Path::new(r"\\?\C:\NUL").simplify_verbatim().to_str().unwrap());
+}
+
+#[test]
+fn absolutize_virtually_4() {
+    assert!(Path::new(r"C:\root2\a").absolutize_virtually(r"C:\root").is_err());
+    assert!(Path::new(r"\a").absolutize_virtually(r"C:\root").is_err());
+
+    assert_eq!(
+        r"C:\ROOT\a",
+        Path::new(r"C:\ROOT\a").absolutize_virtually(r"c:\root").unwrap().to_str().unwrap()
+    );
+}